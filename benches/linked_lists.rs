@@ -0,0 +1,94 @@
+//! Compares the `Rc<RefCell>` based list with the raw pointer one and
+//! `std::collections::LinkedList`. Run with `cargo bench --bench linked_lists`.
+
+use std::{collections::LinkedList, hint::black_box, time::Instant};
+
+use data_structs::{
+    doubly_linked_list::DoublyLinkedList, raw_doubly_linked_list::RawDoublyLinkedList,
+};
+
+const ITEMS: u64 = 1_000_000;
+const RUNS: u32 = 5;
+
+fn time<R>(name: &str, mut f: impl FnMut() -> R) {
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+
+    println!("{name:<28} {:>10.2?}", start.elapsed() / RUNS);
+}
+
+fn main() {
+    time("push/pop rc", || {
+        let mut list = DoublyLinkedList::new();
+
+        for i in 0..ITEMS {
+            list.push(i);
+            list.push_head(i);
+        }
+
+        (0..ITEMS).filter_map(|_| list.pop()).sum::<u64>()
+    });
+    time("push/pop raw", || {
+        let mut list = RawDoublyLinkedList::new();
+
+        for i in 0..ITEMS {
+            list.push(i);
+            list.push_head(i);
+        }
+
+        (0..ITEMS).filter_map(|_| list.pop()).sum::<u64>()
+    });
+    time("push/pop std", || {
+        let mut list = LinkedList::new();
+
+        for i in 0..ITEMS {
+            list.push_back(i);
+            list.push_front(i);
+        }
+
+        (0..ITEMS).filter_map(|_| list.pop_back()).sum::<u64>()
+    });
+
+    time("insert/remove rc", || {
+        let mut list = DoublyLinkedList::new();
+        let head = list.push(0);
+
+        for i in 0..ITEMS {
+            list.insert_after(&head, i);
+        }
+
+        while let Some(next) = list.next(&head) {
+            list.remove(&next);
+        }
+    });
+    time("insert/remove raw", || {
+        let mut list = RawDoublyLinkedList::new();
+        let head = list.push(0);
+
+        for i in 0..ITEMS {
+            list.insert_after(head, i);
+        }
+
+        while let Some(tail) = list.tail().filter(|tail| *tail != head) {
+            list.remove(tail);
+        }
+    });
+
+    let rc: DoublyLinkedList<u64> = (0..ITEMS).fold(DoublyLinkedList::new(), |mut list, i| {
+        list.push(i);
+        list
+    });
+    let raw: RawDoublyLinkedList<u64> =
+        (0..ITEMS).fold(RawDoublyLinkedList::new(), |mut list, i| {
+            list.push(i);
+            list
+        });
+    let std: LinkedList<u64> = (0..ITEMS).collect();
+
+    time("iter rc", || rc.iter().map(|value| *value).sum::<u64>());
+    time("iter raw", || raw.iter().sum::<u64>());
+    time("iter std", || std.iter().sum::<u64>());
+}
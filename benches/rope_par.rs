@@ -0,0 +1,66 @@
+//! Compares the parallel rope operations with their sequential
+//! counterparts. Run with `cargo bench --bench rope_par`. The parallel
+//! operations use one thread per available core, which follows the cpu
+//! affinity on Linux, so `taskset -c 0-3 cargo bench --bench rope_par`
+//! measures them on 4 threads.
+
+use std::{hint::black_box, sync::Arc, thread, time::Instant};
+
+use data_structs::rope::{
+    rope::Rope,
+    rope_edit::MAX_LEAF_LEN,
+    rope_node::{Leaf, RopeNode, RopeNodeType},
+};
+
+const TEXT_LINES: usize = 200_000;
+const RUNS: u32 = 5;
+
+fn time<R>(name: &str, mut f: impl FnMut() -> R) {
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+
+    println!("{name:<28} {:>10.2?}", start.elapsed() / RUNS);
+}
+
+fn main() {
+    let text: String = (0..TEXT_LINES)
+        .map(|i| format!("{i:>8} the quick brown fox jumps over the lazy dog\n"))
+        .collect();
+    let rope = Rope::par_from_str(&text);
+
+    println!(
+        "{} MiB, {} threads",
+        text.len() >> 20,
+        thread::available_parallelism().map_or(1, |n| n.get())
+    );
+
+    time("construction sequential", || {
+        // the text is ascii, so any byte offset is a char boundary
+        Rope::from_iter((0..text.len()).step_by(MAX_LEAF_LEN).map(|start| {
+            let end = (start + MAX_LEAF_LEN).min(text.len());
+
+            RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf {
+                value: text[start..end].to_string(),
+            })))
+        }))
+    });
+    time("construction parallel", || Rope::par_from_str(&text));
+
+    time("line count sequential", || {
+        rope.iter()
+            .map(|n| n.map_leaf().map_or(0, |l| l.value.matches('\n').count()))
+            .sum::<usize>()
+    });
+    time("line count parallel", || rope.par_line_count());
+
+    time("find_all sequential", || {
+        rope.to_string()
+            .match_indices("lazy")
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>()
+    });
+    time("find_all parallel", || rope.par_find_all("lazy").len());
+}
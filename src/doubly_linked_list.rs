@@ -1,250 +1,725 @@
+pub mod list_cursor;
+pub mod list_filter;
+pub mod list_iter;
+pub mod list_sort;
+pub mod list_splice;
+
 use std::{
-    cell::{RefCell, RefMut},
-    fmt::Debug,
-    rc::Rc,
+    cell::{Ref, RefCell, RefMut},
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    mem,
+    rc::{Rc, Weak},
 };
 
-#[derive(Debug)]
-pub struct DoublyLinkedList<T>
-where
-    T: Clone,
-{
-    pub head: Option<Rc<RefCell<ListItem<T>>>>,
-    pub tail: Option<Rc<RefCell<ListItem<T>>>>,
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListError {
+    Empty,
+    /// The item was removed or belongs to another list.
+    NotInList,
+    /// `index` lies past the end of a list of length `len`.
+    OutOfBounds {
+        index: usize,
+        len: usize,
+    },
 }
 
-#[derive(Debug)]
-pub struct ListItem<T>
-where
-    T: Clone,
-{
-    pub next: Option<Rc<RefCell<ListItem<T>>>>,
-    pub prev: Option<Rc<RefCell<ListItem<T>>>>,
-    pub value: T,
+impl Display for ListError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListError::Empty => write!(f, "list is empty"),
+            ListError::NotInList => write!(f, "item is not linked into this list"),
+            ListError::OutOfBounds { index, len } => {
+                write!(f, "index {index} is out of bounds for length {len}")
+            }
+        }
+    }
 }
 
-impl<T> DoublyLinkedList<T>
-where
-    T: Clone,
-{
+impl std::error::Error for ListError {}
+
+type Link<T> = Rc<RefCell<ListItem<T>>>;
+
+pub struct DoublyLinkedList<T> {
+    head: Option<Link<T>>,
+    tail: Option<Link<T>>,
+    len: usize,
+    owner: Rc<ListOwner>,
+}
+
+struct ListItem<T> {
+    next: Option<Link<T>>,
+    /// Weak so that neighbours don't keep each other alive.
+    prev: Option<Weak<RefCell<ListItem<T>>>>,
+    owner: Rc<ListOwner>,
+    value: T,
+}
+
+/// Identity of a list, shared by its items. When all items of a list are
+/// moved into another one its identity is merged into the other one, so
+/// they don't have to be visited.
+#[derive(Default)]
+struct ListOwner {
+    merged_into: RefCell<Option<Rc<ListOwner>>>,
+}
+
+impl ListOwner {
+    /// Identity `owner` was merged into last. Every identity on the way is
+    /// pointed straight at it, so chains of merges stay short.
+    fn root(owner: &Rc<ListOwner>) -> Rc<ListOwner> {
+        let mut root = Rc::clone(owner);
+
+        loop {
+            let merged_into = root.merged_into.borrow().clone();
+
+            match merged_into {
+                Some(merged_into) => root = merged_into,
+                None => break,
+            }
+        }
+
+        let mut owner = Rc::clone(owner);
+
+        while !Rc::ptr_eq(&owner, &root) {
+            match owner.merged_into.replace(Some(Rc::clone(&root))) {
+                Some(merged_into) => owner = merged_into,
+                None => break,
+            }
+        }
+
+        root
+    }
+}
+
+impl Drop for ListOwner {
+    /// Drops a chain of merged identities one at a time, dropping it
+    /// recursively could overflow the stack.
+    fn drop(&mut self) {
+        let mut next = self.merged_into.get_mut().take();
+
+        while let Some(owner) = next {
+            next = Rc::into_inner(owner).and_then(|mut owner| owner.merged_into.get_mut().take());
+        }
+    }
+}
+
+/// Stable reference to an item of a [`DoublyLinkedList`]. It doesn't keep
+/// the item alive, and lists reject it once the item is removed or if it
+/// belongs to another list.
+pub struct NodeHandle<T>(Weak<RefCell<ListItem<T>>>);
+
+impl<T> Clone for NodeHandle<T> {
+    fn clone(&self) -> Self {
+        NodeHandle(Weak::clone(&self.0))
+    }
+}
+
+impl<T> PartialEq for NodeHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T> Eq for NodeHandle<T> {}
+
+impl<T> Debug for NodeHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("NodeHandle").field(&self.0.as_ptr()).finish()
+    }
+}
+
+impl<T> Default for DoublyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> DoublyLinkedList<T> {
     pub fn new() -> Self {
         DoublyLinkedList {
             head: None,
             tail: None,
+            len: 0,
+            owner: Rc::new(ListOwner::default()),
         }
     }
 
-    pub fn push_head(&mut self, value: T) {
-        match self.head.take() {
-            Some(head) => {
-                let new_head = Rc::new(RefCell::new(ListItem {
-                    value,
-                    next: Some(head.clone()),
-                    prev: None,
-                }));
+    pub fn len(&self) -> usize {
+        self.len
+    }
 
-                head.borrow_mut().prev = Some(Rc::clone(&new_head));
-                self.head = Some(new_head);
-            }
-            None => {
-                self.head = Some(Rc::new(RefCell::new(ListItem {
-                    next: None,
-                    prev: None,
-                    value,
-                })));
-                self.sync_tail();
-            }
-        }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
-    pub fn push(&mut self, value: T) {
-        match self.tail.take() {
-            Some(tail) => {
-                let new_tail = Rc::new(RefCell::new(ListItem {
-                    value,
-                    next: None,
-                    prev: Some(tail.clone()),
-                }));
+    pub fn head(&self) -> Option<NodeHandle<T>> {
+        self.head.as_ref().map(handle)
+    }
 
-                tail.borrow_mut().next = Some(Rc::clone(&new_tail));
-                self.tail = Some(new_tail);
-            }
-            None => {
-                self.tail = Some(Rc::new(RefCell::new(ListItem {
-                    next: None,
-                    prev: None,
-                    value,
-                })));
-                self.sync_head();
-
-                return;
-            }
-        }
+    pub fn tail(&self) -> Option<NodeHandle<T>> {
+        self.tail.as_ref().map(handle)
     }
 
-    pub fn pop_head(&mut self) -> Option<T> {
-        match self.head.take() {
-            Some(head) => {
-                let new_head = head.borrow_mut().next.clone();
+    pub fn push_head(&mut self, value: T) -> NodeHandle<T> {
+        self.link(None, self.head.clone(), value)
+    }
 
-                let new_head = match new_head {
-                    Some(new_head) => {
-                        new_head.borrow_mut().prev = None;
+    pub fn push(&mut self, value: T) -> NodeHandle<T> {
+        self.link(self.tail.clone(), None, value)
+    }
 
-                        Some(new_head)
-                    }
-                    None => None,
-                };
+    pub fn pop_head(&mut self) -> Option<T> {
+        self.try_pop_head().ok()
+    }
 
-                self.head = new_head;
-                self.sync_tail();
+    pub fn try_pop_head(&mut self) -> Result<T, Error> {
+        let head = self.head.clone().ok_or(ListError::Empty)?;
 
-                Some(head.borrow_mut().value.clone())
-            }
-            None => None,
-        }
+        Ok(self.unlink(head))
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        match self.tail.take() {
-            Some(tail) => {
-                let new_tail = tail.borrow_mut().prev.clone();
+        self.try_pop().ok()
+    }
+
+    pub fn try_pop(&mut self) -> Result<T, Error> {
+        let tail = self.tail.clone().ok_or(ListError::Empty)?;
+
+        Ok(self.unlink(tail))
+    }
+
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|head| Ref::map(head.borrow(), |item| &item.value))
+    }
+
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
+        self.tail
+            .as_ref()
+            .map(|tail| Ref::map(tail.borrow(), |item| &item.value))
+    }
+
+    pub fn contains(&self, node: &NodeHandle<T>) -> bool {
+        self.find(node).is_ok()
+    }
 
-                let new_tail = match new_tail {
-                    Some(new_tail) => {
-                        new_tail.borrow_mut().next = None;
+    /// Value of `node`, `None` if it isn't linked into this list.
+    pub fn get_node(&self, node: &NodeHandle<T>) -> Option<Ref<'_, T>> {
+        let item = self.find(node).ok()?;
 
-                        Some(new_tail)
-                    }
-                    None => None,
-                };
+        // SAFETY: the item is linked into `self`, which stays borrowed.
+        Some(unsafe { value(&item) })
+    }
+
+    pub fn get_node_mut(&mut self, node: &NodeHandle<T>) -> Option<RefMut<'_, T>> {
+        let item = self.find(node).ok()?;
+
+        // SAFETY: the item is linked into `self`, which stays borrowed.
+        Some(unsafe { value_mut(&item) })
+    }
+
+    /// Value at `index`, walking from the closer end.
+    pub fn get(&self, index: usize) -> Option<Ref<'_, T>> {
+        let item = self.item_at(index)?;
 
-                self.tail = new_tail;
-                self.sync_head();
+        // SAFETY: the item is linked into `self`, which stays borrowed.
+        Some(unsafe { value(&item) })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<RefMut<'_, T>> {
+        let item = self.item_at(index)?;
 
-                Some(tail.borrow_mut().value.clone())
+        // SAFETY: the item is linked into `self`, which stays borrowed.
+        Some(unsafe { value_mut(&item) })
+    }
+
+    pub fn handle_at(&self, index: usize) -> Option<NodeHandle<T>> {
+        self.item_at(index).as_ref().map(handle)
+    }
+
+    /// Panics if `index > len`, see [`DoublyLinkedList::try_insert_at`].
+    pub fn insert_at(&mut self, index: usize, value: T) -> NodeHandle<T> {
+        match self.try_insert_at(index, value) {
+            Ok(handle) => handle,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Inserts `value` so that it ends up at `index`, walking from the
+    /// closer end.
+    pub fn try_insert_at(&mut self, index: usize, value: T) -> Result<NodeHandle<T>, Error> {
+        if index > self.len {
+            return Err(ListError::OutOfBounds {
+                index,
+                len: self.len,
             }
-            None => None,
+            .into());
+        }
+
+        let next = self.item_at(index);
+        let prev = match next.as_ref() {
+            Some(next) => prev_of(next),
+            None => self.tail.clone(),
+        };
+
+        Ok(self.link(prev, next, value))
+    }
+
+    /// Panics if `index >= len`, see [`DoublyLinkedList::try_remove_at`].
+    pub fn remove_at(&mut self, index: usize) -> T {
+        match self.try_remove_at(index) {
+            Ok(value) => value,
+            Err(err) => panic!("{err}"),
         }
     }
 
-    pub fn iter(&self) -> DoublyLinkedListIter<T> {
-        DoublyLinkedListIter(self.head.clone())
+    pub fn try_remove_at(&mut self, index: usize) -> Result<T, Error> {
+        let item = self.item_at(index).ok_or(ListError::OutOfBounds {
+            index,
+            len: self.len,
+        })?;
+
+        Ok(self.unlink(item))
+    }
+
+    /// Item after `node`, `None` at the end or if `node` isn't linked into
+    /// this list.
+    pub fn next(&self, node: &NodeHandle<T>) -> Option<NodeHandle<T>> {
+        let item = self.find(node).ok()?;
+        let next = item.borrow().next.as_ref().map(handle);
+
+        next
+    }
+
+    pub fn prev(&self, node: &NodeHandle<T>) -> Option<NodeHandle<T>> {
+        let item = self.find(node).ok()?;
+        let prev = item.borrow().prev.clone().map(NodeHandle);
+
+        prev
+    }
+
+    pub fn handles(&self) -> Handles<'_, T> {
+        Handles {
+            next: self.head.clone(),
+            marker: PhantomData,
+        }
     }
 
     pub fn foreach<F>(&mut self, mut f: F)
     where
-        F: FnMut(RefMut<ListItem<T>>),
+        F: FnMut(RefMut<T>),
     {
-        for item in self.iter() {
-            f(item.borrow_mut());
+        for item in self.items() {
+            f(RefMut::map(item.borrow_mut(), |item| &mut item.value));
+        }
+    }
+
+    /// Panics if `node` isn't linked into this list, see
+    /// [`DoublyLinkedList::try_insert_after`].
+    pub fn insert_after(&mut self, node: &NodeHandle<T>, value: T) -> NodeHandle<T> {
+        match self.try_insert_after(node, value) {
+            Ok(handle) => handle,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_insert_after(
+        &mut self,
+        node: &NodeHandle<T>,
+        value: T,
+    ) -> Result<NodeHandle<T>, Error> {
+        let item = self.find(node)?;
+        let next = next_of(&item);
+
+        Ok(self.link(Some(item), next, value))
+    }
+
+    /// Panics if `node` isn't linked into this list, see
+    /// [`DoublyLinkedList::try_insert_before`].
+    pub fn insert_before(&mut self, node: &NodeHandle<T>, value: T) -> NodeHandle<T> {
+        match self.try_insert_before(node, value) {
+            Ok(handle) => handle,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_insert_before(
+        &mut self,
+        node: &NodeHandle<T>,
+        value: T,
+    ) -> Result<NodeHandle<T>, Error> {
+        let item = self.find(node)?;
+        let prev = prev_of(&item);
+
+        Ok(self.link(prev, Some(item), value))
+    }
+
+    /// Panics if `node` isn't linked into this list, see
+    /// [`DoublyLinkedList::try_remove`].
+    pub fn remove(&mut self, node: &NodeHandle<T>) -> T {
+        match self.try_remove(node) {
+            Ok(value) => value,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Unlinks `node` and moves its value out. Handles to it are rejected
+    /// afterwards with [`ListError::NotInList`].
+    pub fn try_remove(&mut self, node: &NodeHandle<T>) -> Result<T, Error> {
+        let item = self.find(node)?;
+
+        Ok(self.unlink(item))
+    }
+
+    /// Resolves `node` to its item if it is linked into this list.
+    fn find(&self, node: &NodeHandle<T>) -> Result<Link<T>, ListError> {
+        let item = node.0.upgrade().ok_or(ListError::NotInList)?;
+
+        let owner = ListOwner::root(&item.borrow().owner);
+
+        if !Rc::ptr_eq(&owner, &self.owner) {
+            return Err(ListError::NotInList);
+        }
+
+        // lets go of the merged identities, unless the value is borrowed
+        if let Ok(mut item) = item.try_borrow_mut() {
+            item.owner = owner;
+        }
+
+        Ok(item)
+    }
+
+    fn item_at(&self, index: usize) -> Option<Link<T>> {
+        if index >= self.len {
+            return None;
+        }
+
+        match index < self.len / 2 {
+            true => self.items().nth(index),
+            false => std::iter::successors(self.tail.clone(), prev_of).nth(self.len - 1 - index),
         }
     }
 
-    pub fn insert_after(&mut self, node: &Rc<RefCell<ListItem<T>>>, value: T) {
-        let next_node = node.borrow().next.clone();
-        let new_node = Rc::new(RefCell::new(ListItem {
-            next: next_node.clone(),
-            prev: Some(Rc::clone(&node)),
+    fn items(&self) -> impl Iterator<Item = Link<T>> + '_ {
+        std::iter::successors(self.head.clone(), |item| item.borrow().next.clone())
+    }
+
+    /// Links a new item between `prev` and `next`, which have to be
+    /// neighbours in this list, or its ends.
+    fn link(&mut self, prev: Option<Link<T>>, next: Option<Link<T>>, value: T) -> NodeHandle<T> {
+        let item = Rc::new(RefCell::new(ListItem {
+            next: None,
+            prev: None,
+            owner: Rc::clone(&self.owner),
             value,
         }));
 
-        match next_node.as_ref() {
-            Some(next_node) => next_node.borrow_mut().prev = Some(Rc::clone(&new_node)),
-            None => self.tail = Some(Rc::clone(&new_node)),
+        self.attach(prev, next, &item);
+        self.len += 1;
+
+        handle(&item)
+    }
+
+    /// Unlinks `item`, which has to be linked into this list, and moves its
+    /// value out.
+    fn unlink(&mut self, item: Link<T>) -> T {
+        self.detach(&item);
+        self.len -= 1;
+
+        match Rc::try_unwrap(item) {
+            Ok(item) => item.into_inner().value,
+            Err(_) => unreachable!("unlinked items are only referenced by weak handles"),
+        }
+    }
+
+    /// Links the detached `item` between `prev` and `next`, which have to be
+    /// neighbours in this list, or its ends. Leaves `len` alone.
+    fn attach(&mut self, prev: Option<Link<T>>, next: Option<Link<T>>, item: &Link<T>) {
+        {
+            let mut item = item.borrow_mut();
+            item.next = next.clone();
+            item.prev = prev.as_ref().map(Rc::downgrade);
+        }
+
+        match prev {
+            Some(prev) => prev.borrow_mut().next = Some(Rc::clone(item)),
+            None => self.head = Some(Rc::clone(item)),
+        }
+
+        match next {
+            Some(next) => next.borrow_mut().prev = Some(Rc::downgrade(item)),
+            None => self.tail = Some(Rc::clone(item)),
+        }
+    }
+
+    /// Takes `item`, which has to be linked into this list, out from between
+    /// its neighbours. Leaves `len` alone.
+    fn detach(&mut self, item: &Link<T>) {
+        let (prev, next) = {
+            let mut item = item.borrow_mut();
+            (
+                item.prev.take().and_then(|prev| prev.upgrade()),
+                item.next.take(),
+            )
         };
 
-        node.borrow_mut().next = Some(new_node);
+        match prev.as_ref() {
+            Some(prev) => prev.borrow_mut().next = next.clone(),
+            None => self.head = next.clone(),
+        }
+
+        match next.as_ref() {
+            Some(next) => next.borrow_mut().prev = prev.as_ref().map(Rc::downgrade),
+            None => self.tail = prev,
+        }
     }
 
-    pub fn insert_before(&mut self, node: &Rc<RefCell<ListItem<T>>>, value: T) {
-        let prev_node = node.borrow().prev.clone();
-        let new_node = Rc::new(RefCell::new(ListItem {
-            prev: prev_node.clone(),
-            next: Some(Rc::clone(&node)),
-            value,
-        }));
+    /// Moves `first` and the items after it into a new list. Identities
+    /// can be merged in O(1), but not told apart again without visiting the
+    /// items, so only the shorter side is visited: it takes on a new
+    /// identity and the longer one keeps the current one. Splitting costs
+    /// O(min(kept, moved)).
+    fn split_from(&mut self, first: Option<Link<T>>) -> DoublyLinkedList<T> {
+        let mut rest = DoublyLinkedList::new();
 
-        match prev_node.as_ref() {
-            Some(next_node) => next_node.borrow_mut().next = Some(Rc::clone(&new_node)),
-            None => self.head = Some(Rc::clone(&new_node)),
+        let Some(first) = first else {
+            return rest;
         };
 
-        node.borrow_mut().prev = Some(new_node);
-    }
-
-    pub fn remove(&mut self, node: &Rc<RefCell<ListItem<T>>>) {
-        match node.borrow().prev.as_ref() {
-            Some(prev) => match node.borrow().next.as_ref() {
-                Some(next) => {
-                    prev.borrow_mut().next = Some(Rc::clone(&next));
-                    next.borrow_mut().prev = Some(Rc::clone(&prev));
-                }
-                None => {
-                    prev.borrow_mut().next = None;
-                    self.sync_tail();
-                }
-            },
+        let prev = first
+            .borrow_mut()
+            .prev
+            .take()
+            .and_then(|prev| prev.upgrade());
+
+        rest.tail = match prev.clone() {
+            Some(prev) => {
+                prev.borrow_mut().next = None;
+                self.tail.replace(prev)
+            }
             None => {
-                self.head = node.borrow().next.clone();
-                self.sync_tail();
+                self.head = None;
+                self.tail.take()
             }
+        };
+        rest.head = Some(first);
+
+        // walks both sides away from the cut until the shorter one ends
+        let mut kept = std::iter::successors(prev, prev_of);
+        let mut moved = std::iter::successors(rest.head.clone(), next_of);
+        let mut shorter = 0;
+
+        let moved_len = loop {
+            match (moved.next(), kept.next()) {
+                (None, _) => break shorter,
+                (Some(_), None) => break self.len - shorter,
+                (Some(_), Some(_)) => shorter += 1,
+            }
+        };
+
+        match moved_len == shorter {
+            true => rest.relabel(),
+            false => {
+                mem::swap(&mut self.owner, &mut rest.owner);
+                self.relabel();
+            }
+        }
+
+        rest.len = moved_len;
+        self.len -= moved_len;
+
+        rest
+    }
+
+    /// Points every item at the identity of this list.
+    fn relabel(&self) {
+        for item in self.items() {
+            item.borrow_mut().owner = Rc::clone(&self.owner);
         }
     }
 
-    fn sync_tail(&mut self) {
-        if self.head.is_some() && self.head.as_ref().unwrap().borrow().next.is_none() {
-            self.tail = Some(Rc::clone(&self.head.as_ref().unwrap()));
+    /// Links the items of `other` between `prev` and `next`, which have to
+    /// be neighbours in this list, or its ends.
+    fn splice_between(&mut self, prev: Option<Link<T>>, next: Option<Link<T>>, mut other: Self) {
+        let (Some(head), Some(tail)) = (other.head.take(), other.tail.take()) else {
             return;
+        };
+
+        other
+            .owner
+            .merged_into
+            .replace(Some(Rc::clone(&self.owner)));
+        self.len += mem::take(&mut other.len);
+        head.borrow_mut().prev = prev.as_ref().map(Rc::downgrade);
+
+        match next {
+            Some(next) => {
+                next.borrow_mut().prev = Some(Rc::downgrade(&tail));
+                tail.borrow_mut().next = Some(next);
+            }
+            None => self.tail = Some(tail),
         }
 
-        if self.head.is_none() {
-            self.tail = None;
+        match prev {
+            Some(prev) => prev.borrow_mut().next = Some(head),
+            None => self.head = Some(head),
         }
     }
+}
 
-    fn sync_head(&mut self) {
-        if self.tail.is_some() && self.tail.as_ref().unwrap().borrow().prev.is_none() {
-            self.head = Some(Rc::clone(&self.tail.as_ref().unwrap()));
-            return;
+fn handle<T>(item: &Link<T>) -> NodeHandle<T> {
+    NodeHandle(Rc::downgrade(item))
+}
+
+fn next_of<T>(item: &Link<T>) -> Option<Link<T>> {
+    item.borrow().next.clone()
+}
+
+fn prev_of<T>(item: &Link<T>) -> Option<Link<T>> {
+    item.borrow().prev.as_ref().and_then(Weak::upgrade)
+}
+
+/// Borrows the value of an item for as long as its list is borrowed.
+///
+/// # Safety
+///
+/// `item` has to be linked into a list that stays borrowed for `'a`. Items
+/// are owned by their list and only unlinked through `&mut` to it.
+unsafe fn value<'a, T>(item: &Link<T>) -> Ref<'a, T> {
+    let item = unsafe { &*Rc::as_ptr(item) };
+
+    Ref::map(item.borrow(), |item| &item.value)
+}
+
+/// See [`value`].
+///
+/// # Safety
+///
+/// `item` has to be linked into a list that stays borrowed for `'a`.
+unsafe fn value_mut<'a, T>(item: &Link<T>) -> RefMut<'a, T> {
+    let item = unsafe { &*Rc::as_ptr(item) };
+
+    RefMut::map(item.borrow_mut(), |item| &mut item.value)
+}
+
+impl<T> Drop for DoublyLinkedList<T> {
+    /// Unlinks the items one by one, dropping the chain of `next` links
+    /// recursively would overflow the stack on long lists.
+    fn drop(&mut self) {
+        self.tail = None;
+        let mut next = self.head.take();
+
+        while let Some(item) = next {
+            next = item.borrow_mut().next.take();
         }
+    }
+}
+
+impl<T: Debug> Debug for DoublyLinkedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
 
-        if self.tail.is_none() {
-            self.head = None;
+        for item in self.items() {
+            list.entry(&item.borrow().value);
         }
+
+        list.finish()
     }
 }
 
-pub struct DoublyLinkedListIter<T: Clone>(Option<Rc<RefCell<ListItem<T>>>>);
+/// Handles to the items of a list, from head to tail.
+pub struct Handles<'a, T> {
+    next: Option<Link<T>>,
+    /// Items can't be unlinked while the list is borrowed.
+    marker: PhantomData<&'a DoublyLinkedList<T>>,
+}
+
+impl<T> Iterator for Handles<'_, T> {
+    type Item = NodeHandle<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current_item = self.next.take()?;
+        self.next = current_item.borrow().next.clone();
+
+        Some(handle(&current_item))
+    }
+}
 
-impl<T> Iterator for DoublyLinkedListIter<T>
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for DoublyLinkedList<T>
 where
-    T: Clone,
+    T: serde::Serialize,
 {
-    type Item = Rc<RefCell<ListItem<T>>>;
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.0.is_none() {
-            return None;
+        let mut seq = serializer.serialize_seq(None)?;
+
+        for item in self.items() {
+            seq.serialize_element(&item.borrow().value)?;
         }
 
-        let current_item = &self.0.clone().unwrap();
-        let next = &current_item.borrow().next;
+        seq.end()
+    }
+}
 
-        let next = next.as_ref();
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for DoublyLinkedList<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut list = DoublyLinkedList::new();
 
-        self.0 = match next {
-            Some(next) => Some(Rc::clone(next)),
-            None => None,
-        };
+        for value in Vec::<T>::deserialize(deserializer)? {
+            list.push(value);
+        }
 
-        Some(current_item.clone())
+        Ok(list)
+    }
+}
+
+/// Helpers shared by the tests of the list and its submodules.
+#[cfg(test)]
+mod test_helpers {
+    use super::DoublyLinkedList;
+
+    pub fn list_of(values: &[i32]) -> DoublyLinkedList<i32> {
+        let mut list = DoublyLinkedList::new();
+
+        for value in values {
+            list.push(*value);
+        }
+
+        list
+    }
+
+    pub fn values<T: Copy>(list: &DoublyLinkedList<T>) -> Vec<T> {
+        list.handles()
+            .map(|h| *list.get_node(&h).unwrap())
+            .collect()
+    }
+
+    /// Walks both ways to check the links agree with `len`.
+    pub fn assert_linked<T: Copy + PartialEq + std::fmt::Debug>(list: &DoublyLinkedList<T>) {
+        let forward = values(list);
+        let mut backward: Vec<T> = list.iter().rev().map(|v| *v).collect();
+        backward.reverse();
+
+        assert_eq!(forward, backward);
+        assert_eq!(forward.len(), list.len());
+        assert_eq!(list.handles().count(), list.len());
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::test_helpers::values;
     use super::*;
+    use std::cell::Cell;
 
     #[test]
     fn push_pop_head_test() {
@@ -271,7 +746,7 @@ mod tests {
     }
 
     #[test]
-    fn iter_test() {
+    fn handles_test() {
         let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
         list.push(5);
         list.push(25);
@@ -280,14 +755,13 @@ mod tests {
         list.push(-25);
         list.push(-5);
 
-        let mut iter = list.iter();
-        assert_eq!(iter.next().unwrap().borrow().value, 5);
-        assert_eq!(iter.next().unwrap().borrow().value, 25);
-        assert_eq!(iter.next().unwrap().borrow().value, 50);
-        assert_eq!(iter.next().unwrap().borrow().value, -50);
-        assert_eq!(iter.next().unwrap().borrow().value, -25);
-        assert_eq!(iter.next().unwrap().borrow().value, -5);
-        assert!(iter.next().is_none());
+        assert_eq!(values(&list), [5, 25, 50, -50, -25, -5]);
+        assert_eq!(format!("{list:?}"), "[5, 25, 50, -50, -25, -5]");
+
+        let second = list.next(&list.head().unwrap()).unwrap();
+        assert_eq!(list.prev(&second), list.head());
+        assert_eq!(list.handles().nth(1), Some(second));
+        assert!(list.next(&list.tail().unwrap()).is_none());
     }
 
     #[test]
@@ -295,13 +769,14 @@ mod tests {
         let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
         list.push(5);
         list.push(25);
-        list.push(50);
+        let last = list.push(50);
 
-        list.foreach(|mut i| i.value = i.value * 5);
+        list.foreach(|mut i| *i *= 5);
+        *list.get_node_mut(&last).unwrap() += 1;
 
         assert_eq!(list.pop_head().unwrap(), 25);
         assert_eq!(list.pop_head().unwrap(), 125);
-        assert_eq!(list.pop_head().unwrap(), 250);
+        assert_eq!(list.pop_head().unwrap(), 251);
         assert!(list.pop().is_none());
     }
 
@@ -309,99 +784,73 @@ mod tests {
     fn insert_after_test() {
         let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
         list.push(5);
-        list.push(25);
+        let second_item = list.push(25);
         list.push(50);
 
-        let mut iter = list.iter();
-        _ = iter.next();
-        let second_item = iter.next().unwrap();
-
         list.insert_after(&second_item, 40);
 
-        let mut iter = list.iter();
-        assert_eq!(iter.next().unwrap().borrow().value, 5);
-        assert_eq!(iter.next().unwrap().borrow().value, 25);
-        assert_eq!(iter.next().unwrap().borrow().value, 40);
-        assert_eq!(iter.next().unwrap().borrow().value, 50);
-        assert!(iter.next().is_none());
+        assert_eq!(values(&list), [5, 25, 40, 50]);
     }
 
     #[test]
     fn insert_after_one_item_test() {
         let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
-        list.push(5);
+        let first = list.push(5);
 
-        let first = list.head.clone();
-        list.insert_after(&first.as_ref().unwrap(), 40);
+        let inserted = list.insert_after(&first, 40);
 
-        let mut iter = list.iter();
-        assert_eq!(iter.next().unwrap().borrow().value, 5);
-        assert_eq!(iter.next().unwrap().borrow().value, 40);
-        assert_eq!(list.tail.as_ref().unwrap().borrow().value, 40);
-        assert!(iter.next().is_none());
+        assert_eq!(values(&list), [5, 40]);
+        assert_eq!(list.tail(), Some(inserted));
+        assert_eq!(*list.peek_back().unwrap(), 40);
     }
 
     #[test]
     fn insert_before_test() {
         let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
         list.push(5);
-        list.push(25);
+        let second_item = list.push(25);
         list.push(50);
 
-        let mut iter = list.iter();
-        _ = iter.next();
-        let second_item = iter.next().unwrap();
-
         list.insert_before(&second_item, 40);
 
-        let mut iter = list.iter();
-        assert_eq!(iter.next().unwrap().borrow().value, 5);
-        assert_eq!(iter.next().unwrap().borrow().value, 40);
-        assert_eq!(iter.next().unwrap().borrow().value, 25);
-        assert_eq!(iter.next().unwrap().borrow().value, 50);
-        assert!(iter.next().is_none());
+        assert_eq!(values(&list), [5, 40, 25, 50]);
     }
 
     #[test]
     fn insert_before_one_item_test() {
         let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
-        list.push(5);
+        let first = list.push(5);
 
-        let first = list.head.clone();
-        list.insert_before(&first.as_ref().unwrap(), 40);
+        let inserted = list.insert_before(&first, 40);
 
-        let mut iter = list.iter();
-        assert_eq!(iter.next().unwrap().borrow().value, 40);
-        assert_eq!(iter.next().unwrap().borrow().value, 5);
-        assert_eq!(list.head.as_ref().unwrap().borrow().value, 40);
-        assert!(iter.next().is_none());
+        assert_eq!(values(&list), [40, 5]);
+        assert_eq!(list.head(), Some(inserted));
+        assert_eq!(*list.peek_front().unwrap(), 40);
     }
 
     #[test]
     fn remove_one_item_list_test() {
         let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
-        list.push(5);
+        let first = list.push(5);
 
-        let first = list.head.clone();
-        list.remove(&first.as_ref().unwrap());
+        assert_eq!(list.remove(&first), 5);
 
-        let mut iter = list.iter();
-        assert!(iter.next().is_none());
-        assert!(list.head.as_ref().is_none());
-        assert!(list.tail.as_ref().is_none());
+        assert!(list.handles().next().is_none());
+        assert!(list.head().is_none());
+        assert!(list.tail().is_none());
+        assert!(list.is_empty());
     }
 
     #[test]
     fn remove_two_items_list_firt_test() {
         let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
-        list.push(5);
+        let first = list.push(5);
         list.push(25);
 
-        let first = list.head.clone();
-        list.remove(&first.as_ref().unwrap());
+        list.remove(&first);
 
-        assert_eq!(list.head.as_ref().unwrap().borrow().value, 25);
-        assert_eq!(list.tail.as_ref().unwrap().borrow().value, 25);
+        assert_eq!(*list.peek_front().unwrap(), 25);
+        assert_eq!(*list.peek_back().unwrap(), 25);
     }
 
     #[test]
@@ -410,11 +859,11 @@ mod tests {
         list.push(5);
         list.push(25);
 
-        let second = list.head.as_ref().unwrap().borrow().next.clone();
-        list.remove(&second.as_ref().unwrap());
+        let second = list.next(&list.head().unwrap()).unwrap();
+        list.remove(&second);
 
-        assert_eq!(list.head.as_ref().unwrap().borrow().value, 5);
-        assert_eq!(list.tail.as_ref().unwrap().borrow().value, 5);
+        assert_eq!(*list.peek_front().unwrap(), 5);
+        assert_eq!(*list.peek_back().unwrap(), 5);
     }
 
     #[test]
@@ -424,10 +873,219 @@ mod tests {
         list.push(25);
         list.push(50);
 
-        let second = list.head.as_ref().unwrap().borrow().next.clone();
-        list.remove(&second.as_ref().unwrap());
+        let second = list.next(&list.head().unwrap()).unwrap();
+        list.remove(&second);
+
+        assert_eq!(*list.peek_front().unwrap(), 5);
+        assert_eq!(*list.peek_back().unwrap(), 50);
+    }
+
+    #[test]
+    fn remove_tail_test() {
+        let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        list.push(5);
+        list.push(25);
+        let tail = list.push(50);
+
+        assert_eq!(list.remove(&tail), 50);
+
+        assert_eq!(*list.peek_back().unwrap(), 25);
+        assert_eq!(list.pop(), Some(25));
+        assert_eq!(list.pop(), Some(5));
+    }
+
+    #[test]
+    fn try_errors_test() {
+        let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        let mut other: DoublyLinkedList<i32> = DoublyLinkedList::new();
+
+        assert_eq!(list.try_pop(), Err(ListError::Empty.into()));
+        assert_eq!(list.try_pop_head(), Err(ListError::Empty.into()));
+
+        let head = list.push(5);
+        list.push(25);
+        other.push(1);
+        let foreign = other.push(50);
+        other.push(100);
+
+        assert_eq!(list.try_remove(&foreign), Err(ListError::NotInList.into()));
+        assert_eq!(
+            list.try_insert_after(&foreign, 1),
+            Err(ListError::NotInList.into())
+        );
+        assert!(list.get_node(&foreign).is_none() && !list.contains(&foreign));
+        assert_eq!(values(&other), [1, 50, 100]);
+
+        assert_eq!(list.try_remove(&head), Ok(5));
+        assert_eq!(list.try_remove(&head), Err(ListError::NotInList.into()));
+        assert_eq!(
+            list.try_insert_before(&head, 1),
+            Err(ListError::NotInList.into())
+        );
+
+        assert_eq!(list.try_pop(), Ok(25));
+        assert_eq!(list.try_pop(), Err(Error::List(ListError::Empty)));
+    }
+
+    #[test]
+    fn positional_test() {
+        let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        assert_eq!(list.len(), 0);
+
+        for value in [10, 20, 30, 40, 50] {
+            list.push(value);
+        }
+
+        assert_eq!(list.len(), 5);
+        assert_eq!(*list.get(0).unwrap(), 10);
+        assert_eq!(*list.get(3).unwrap(), 40);
+        assert!(list.get(5).is_none());
+
+        *list.get_mut(4).unwrap() += 5;
+        list.insert_at(0, 0);
+        list.insert_at(6, 60);
+        let handle = list.insert_at(4, 35);
+
+        assert_eq!(values(&list), [0, 10, 20, 30, 35, 40, 55, 60]);
+        assert_eq!(list.handle_at(4), Some(handle));
+        assert_eq!(list.remove_at(6), 55);
+        assert_eq!(list.remove_at(1), 10);
+        assert_eq!(list.len(), 6);
+
+        assert_eq!(
+            list.try_insert_at(7, 1),
+            Err(ListError::OutOfBounds { index: 7, len: 6 }.into())
+        );
+        assert_eq!(
+            list.try_remove_at(6),
+            Err(ListError::OutOfBounds { index: 6, len: 6 }.into())
+        );
+
+        list.pop();
+        list.pop_head();
+        assert_eq!(values(&list), [20, 30, 35, 40]);
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn non_clone_values_test() {
+        let mut list: DoublyLinkedList<Box<dyn Display>> = DoublyLinkedList::new();
+        list.push(Box::new(5));
+        list.push(Box::new("middle"));
+        let head = list.push_head(Box::new('a'));
+
+        assert_eq!(list.peek_front().unwrap().to_string(), "a");
+        assert_eq!(list.peek_back().unwrap().to_string(), "middle");
+
+        assert_eq!(list.pop_head().unwrap().to_string(), "a");
+        assert!(list.get_node(&head).is_none());
+        assert_eq!(list.pop().unwrap().to_string(), "middle");
+        assert_eq!(list.pop().unwrap().to_string(), "5");
+        assert!(list.peek_front().is_none() && list.peek_back().is_none());
+    }
+
+    /// Counts live values, clones included.
+    struct Counted(Rc<Cell<usize>>);
+
+    impl Counted {
+        fn new(live: &Rc<Cell<usize>>) -> Self {
+            live.set(live.get() + 1);
+            Counted(Rc::clone(live))
+        }
+    }
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            Counted::new(&self.0)
+        }
+    }
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() - 1);
+        }
+    }
+
+    #[test]
+    fn no_leak_test() {
+        let live = Rc::new(Cell::new(0));
+
+        {
+            let mut list = DoublyLinkedList::new();
+
+            for _ in 0..10 {
+                list.push(Counted::new(&live));
+                list.push_head(Counted::new(&live));
+            }
+
+            let second = list.next(&list.head().unwrap()).unwrap();
+            list.insert_after(&second, Counted::new(&live));
+            list.insert_before(&second, Counted::new(&live));
+            list.remove(&second);
+
+            list.pop();
+            list.pop_head();
+
+            assert_eq!(live.get(), 19);
+        }
+
+        assert_eq!(live.get(), 0);
+    }
+
+    #[test]
+    fn drop_long_list_test() {
+        let mut list = DoublyLinkedList::new();
+
+        for i in 0..1_000_000 {
+            list.push(i);
+        }
+
+        drop(list);
+    }
+
+    #[test]
+    fn chained_appends_test() {
+        fn chain(len: usize) -> (DoublyLinkedList<usize>, NodeHandle<usize>) {
+            let mut acc = DoublyLinkedList::new();
+            let first = acc.push(0);
+
+            for i in 1..len {
+                let mut list = DoublyLinkedList::new();
+                list.push(i);
+                list.append(&mut acc);
+                acc = list;
+            }
+
+            (acc, first)
+        }
+
+        drop(chain(1_000_000));
+
+        let (list, first) = chain(1_000_000);
+
+        assert!(list.contains(&first));
+        assert!(list.contains(&first));
+        assert_eq!(list.get_node(&first).as_deref(), Some(&0));
+        assert_eq!(list.len(), 1_000_000);
+
+        drop(list);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_test() {
+        let mut list: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        list.push(5);
+        list.push(25);
+        list.push(50);
+
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, "[5,25,50]");
 
-        assert_eq!(list.head.as_ref().unwrap().borrow().value, 5);
-        assert_eq!(list.tail.as_ref().unwrap().borrow().value, 50);
+        let mut restored: DoublyLinkedList<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.pop_head().unwrap(), 5);
+        assert_eq!(restored.pop_head().unwrap(), 25);
+        assert_eq!(restored.pop_head().unwrap(), 50);
+        assert!(restored.pop_head().is_none());
     }
 }
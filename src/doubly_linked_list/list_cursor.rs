@@ -0,0 +1,435 @@
+use std::{
+    cell::{Ref, RefMut},
+    mem,
+};
+
+use super::{handle, next_of, prev_of, value, value_mut, DoublyLinkedList, Link, NodeHandle};
+
+/// Position in a list, either at an item or at the "ghost" position past
+/// the tail and before the head. Moving past either end reaches the ghost,
+/// moving on from it wraps around.
+pub struct Cursor<'a, T> {
+    list: &'a DoublyLinkedList<T>,
+    current: Option<Link<T>>,
+    /// Index of `current`, the length of the list at the ghost position.
+    index: usize,
+}
+
+/// Cursor that can also edit the list, see [`Cursor`].
+pub struct CursorMut<'a, T> {
+    list: &'a mut DoublyLinkedList<T>,
+    current: Option<Link<T>>,
+    index: usize,
+}
+
+impl<T> DoublyLinkedList<T> {
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            current: self.head.clone(),
+            index: 0,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            current: self.tail.clone(),
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    /// Cursor at `node`, `None` if it isn't linked into this list. Walks to
+    /// the head to find the index of `node`.
+    pub fn cursor_at(&self, node: &NodeHandle<T>) -> Option<Cursor<'_, T>> {
+        let current = self.find(node).ok()?;
+
+        Some(Cursor {
+            index: index_of(&current),
+            current: Some(current),
+            list: self,
+        })
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.head.clone(),
+            index: 0,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.tail.clone(),
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    /// See [`DoublyLinkedList::cursor_at`].
+    pub fn cursor_at_mut(&mut self, node: &NodeHandle<T>) -> Option<CursorMut<'_, T>> {
+        let current = self.find(node).ok()?;
+
+        Some(CursorMut {
+            index: index_of(&current),
+            current: Some(current),
+            list: self,
+        })
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub fn move_next(&mut self) {
+        (self.current, self.index) = next_position(self.list, &self.current, self.index);
+    }
+
+    pub fn move_prev(&mut self) {
+        (self.current, self.index) = prev_position(self.list, &self.current, self.index);
+    }
+
+    /// Index of the item at the cursor, `None` at the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        self.current.as_ref().map(|_| self.index)
+    }
+
+    /// Value at the cursor, `None` at the ghost position.
+    pub fn current(&self) -> Option<Ref<'a, T>> {
+        // SAFETY: cursors only hold items of the list they borrow.
+        self.current.as_ref().map(|item| unsafe { value(item) })
+    }
+
+    pub fn peek_next(&self) -> Option<Ref<'a, T>> {
+        // SAFETY: as in `current`.
+        self.next_item().as_ref().map(|item| unsafe { value(item) })
+    }
+
+    pub fn peek_prev(&self) -> Option<Ref<'a, T>> {
+        // SAFETY: as in `current`.
+        self.prev_item().as_ref().map(|item| unsafe { value(item) })
+    }
+
+    pub fn handle(&self) -> Option<NodeHandle<T>> {
+        self.current.as_ref().map(handle)
+    }
+
+    fn next_item(&self) -> Option<Link<T>> {
+        match self.current.as_ref() {
+            Some(current) => next_of(current),
+            None => self.list.head.clone(),
+        }
+    }
+
+    fn prev_item(&self) -> Option<Link<T>> {
+        match self.current.as_ref() {
+            Some(current) => prev_of(current),
+            None => self.list.tail.clone(),
+        }
+    }
+}
+
+impl<T> CursorMut<'_, T> {
+    pub fn move_next(&mut self) {
+        (self.current, self.index) = next_position(self.list, &self.current, self.index);
+    }
+
+    pub fn move_prev(&mut self) {
+        (self.current, self.index) = prev_position(self.list, &self.current, self.index);
+    }
+
+    /// Index of the item at the cursor, `None` at the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        self.current.as_ref().map(|_| self.index)
+    }
+
+    /// Value at the cursor, `None` at the ghost position.
+    pub fn current(&mut self) -> Option<RefMut<'_, T>> {
+        // SAFETY: cursors only hold items of the list they borrow.
+        self.current.as_ref().map(|item| unsafe { value_mut(item) })
+    }
+
+    pub fn peek_next(&mut self) -> Option<RefMut<'_, T>> {
+        // SAFETY: as in `current`.
+        self.next_item()
+            .as_ref()
+            .map(|item| unsafe { value_mut(item) })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<RefMut<'_, T>> {
+        // SAFETY: as in `current`.
+        self.prev_item()
+            .as_ref()
+            .map(|item| unsafe { value_mut(item) })
+    }
+
+    pub fn handle(&self) -> Option<NodeHandle<T>> {
+        self.current.as_ref().map(handle)
+    }
+
+    /// Read-only cursor at the same position.
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self.list,
+            current: self.current.clone(),
+            index: self.index,
+        }
+    }
+
+    /// Inserts after the cursor, at the head at the ghost position.
+    pub fn insert_after(&mut self, value: T) -> NodeHandle<T> {
+        let next = self.next_item();
+
+        if self.current.is_none() {
+            self.index += 1;
+        }
+
+        self.list.link(self.current.clone(), next, value)
+    }
+
+    /// Inserts before the cursor, at the tail at the ghost position.
+    pub fn insert_before(&mut self, value: T) -> NodeHandle<T> {
+        let prev = self.prev_item();
+        self.index += 1;
+
+        self.list.link(prev, self.current.clone(), value)
+    }
+
+    /// Removes the item at the cursor and moves to the next one. Nothing
+    /// happens at the ghost position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let current = self.current.take()?;
+        self.current = next_of(&current);
+
+        Some(self.list.unlink(current))
+    }
+
+    /// Moves the items after the cursor into a new list, all of them at the
+    /// ghost position. O(n) in the number of moved items.
+    pub fn split_after(&mut self) -> DoublyLinkedList<T> {
+        let next = self.next_item();
+        let rest = self.list.split_from(next);
+        self.index = self.index.min(self.list.len);
+
+        rest
+    }
+
+    /// Moves the items before the cursor into a new list, all of them at
+    /// the ghost position. O(n) in the number of kept items.
+    pub fn split_before(&mut self) -> DoublyLinkedList<T> {
+        let rest = self.list.split_from(self.current.clone());
+        self.index = 0;
+
+        mem::replace(self.list, rest)
+    }
+
+    /// Links the items of `other` after the cursor, at the head at the ghost
+    /// position. O(1).
+    pub fn splice_after(&mut self, other: DoublyLinkedList<T>) {
+        let next = self.next_item();
+
+        if self.current.is_none() {
+            self.index += other.len;
+        }
+
+        self.list.splice_between(self.current.clone(), next, other);
+    }
+
+    /// Links the items of `other` before the cursor, at the tail at the
+    /// ghost position. O(1).
+    pub fn splice_before(&mut self, other: DoublyLinkedList<T>) {
+        let prev = self.prev_item();
+        self.index += other.len;
+
+        self.list.splice_between(prev, self.current.clone(), other);
+    }
+
+    fn next_item(&self) -> Option<Link<T>> {
+        match self.current.as_ref() {
+            Some(current) => next_of(current),
+            None => self.list.head.clone(),
+        }
+    }
+
+    fn prev_item(&self) -> Option<Link<T>> {
+        match self.current.as_ref() {
+            Some(current) => prev_of(current),
+            None => self.list.tail.clone(),
+        }
+    }
+}
+
+fn next_position<T>(
+    list: &DoublyLinkedList<T>,
+    current: &Option<Link<T>>,
+    index: usize,
+) -> (Option<Link<T>>, usize) {
+    match current {
+        Some(current) => (next_of(current), index + 1),
+        None => (list.head.clone(), 0),
+    }
+}
+
+fn prev_position<T>(
+    list: &DoublyLinkedList<T>,
+    current: &Option<Link<T>>,
+    index: usize,
+) -> (Option<Link<T>>, usize) {
+    match current {
+        Some(current) if index > 0 => (prev_of(current), index - 1),
+        Some(_) => (None, list.len),
+        None => (list.tail.clone(), list.len.saturating_sub(1)),
+    }
+}
+
+fn index_of<T>(item: &Link<T>) -> usize {
+    std::iter::successors(prev_of(item), prev_of).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doubly_linked_list::test_helpers::{list_of, values};
+
+    #[test]
+    fn cursor_moves_test() {
+        let list = list_of(&[1, 2, 3]);
+        let mut cursor = list.cursor_front();
+
+        assert_eq!(*cursor.current().unwrap(), 1);
+        assert!(cursor.peek_prev().is_none());
+        assert_eq!(*cursor.peek_next().unwrap(), 2);
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(*cursor.current().unwrap(), 3);
+
+        cursor.move_next();
+        assert!(cursor.current().is_none() && cursor.handle().is_none());
+        assert_eq!(*cursor.peek_next().unwrap(), 1);
+        assert_eq!(*cursor.peek_prev().unwrap(), 3);
+
+        cursor.move_next();
+        assert_eq!(cursor.handle(), list.head());
+
+        let mut cursor = list.cursor_back();
+        cursor.move_prev();
+        assert_eq!(*cursor.current().unwrap(), 2);
+
+        let empty: DoublyLinkedList<i32> = DoublyLinkedList::new();
+        assert!(empty.cursor_front().peek_next().is_none());
+    }
+
+    #[test]
+    fn cursor_mut_edit_test() {
+        let mut list = list_of(&[1, 2, 3]);
+        let other = list.push(0);
+        let middle = list.handles().nth(1).unwrap();
+
+        let mut cursor = list.cursor_at_mut(&middle).unwrap();
+        *cursor.current().unwrap() *= 10;
+        *cursor.peek_next().unwrap() += 1;
+        cursor.insert_before(15);
+        cursor.insert_after(25);
+
+        assert_eq!(cursor.remove_current(), Some(20));
+        assert_eq!(*cursor.current().unwrap(), 25);
+        assert_eq!(*cursor.as_cursor().peek_prev().unwrap(), 15);
+
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        assert!(cursor.remove_current().is_none());
+        cursor.insert_after(-1);
+        cursor.insert_before(100);
+
+        assert_eq!(values(&list), [-1, 1, 15, 25, 4, 0, 100]);
+        assert_eq!(list.remove(&other), 0);
+        assert!(list.cursor_at(&middle).is_none());
+    }
+
+    #[test]
+    fn cursor_index_test() {
+        let mut list = list_of(&[1, 2, 3]);
+        let third = list.tail().unwrap();
+
+        let mut cursor = list.cursor_front();
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(list.cursor_at(&third).unwrap().index(), Some(2));
+
+        let mut cursor = list.cursor_at_mut(&third).unwrap();
+        cursor.insert_before(0);
+        cursor.splice_before(list_of(&[7, 8]));
+        assert_eq!(cursor.index(), Some(5));
+
+        cursor.move_next();
+        cursor.insert_after(-1);
+        cursor.insert_before(9);
+        cursor.splice_after(list_of(&[-2]));
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(8));
+
+        assert_eq!(cursor.remove_current(), Some(9));
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.split_before().len(), 1);
+        assert_eq!(cursor.index(), Some(0));
+
+        assert_eq!(values(&list), [-1, 1, 2, 0, 7, 8, 3]);
+        assert_eq!(list.len(), 7);
+    }
+
+    #[test]
+    fn split_test() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        let third = list.handles().nth(2).unwrap();
+        let first = list.head().unwrap();
+
+        let mut cursor = list.cursor_at_mut(&third).unwrap();
+        let after = cursor.split_after();
+        let before = cursor.split_before();
+
+        assert_eq!(values(&before), [1, 2]);
+        assert_eq!(values(&list), [3]);
+        assert_eq!(values(&after), [4]);
+        assert_eq!([before.iter().len(), list.iter().len()], [2, 1]);
+        assert!(list.contains(&third) && before.contains(&first));
+        assert!(!list.contains(&first));
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        assert_eq!(values(&cursor.split_before()), [3]);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn splice_test() {
+        let mut list = list_of(&[1, 5]);
+        let mut inner = list_of(&[2, 3]);
+        let nested = list_of(&[4]);
+        let two = inner.head().unwrap();
+        let four = nested.head().unwrap();
+
+        // `nested` is merged into `inner`, which is merged into `list`
+        inner.cursor_back_mut().splice_after(nested);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_after(inner);
+        cursor.move_prev();
+        cursor.splice_after(list_of(&[0]));
+        cursor.splice_before(list_of(&[6]));
+        cursor.splice_before(DoublyLinkedList::new());
+
+        assert_eq!(values(&list), [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(list.iter().len(), 7);
+        assert_eq!(*list.get_node(&two).unwrap(), 2);
+        assert_eq!(list.remove(&four), 4);
+        assert_eq!(values(&list), [0, 1, 2, 3, 5, 6]);
+    }
+}
@@ -0,0 +1,178 @@
+use std::iter::FusedIterator;
+
+use super::{next_of, DoublyLinkedList, Link};
+
+/// Values unlinked from a list by [`DoublyLinkedList::extract_if`].
+pub struct ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    list: &'a mut DoublyLinkedList<T>,
+    next: Option<Link<T>>,
+    filter: F,
+}
+
+impl<T> DoublyLinkedList<T> {
+    /// Keeps only the values `f` returns true for, in one pass.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.extract_if(|value| !f(value)).for_each(drop);
+    }
+
+    /// Unlinks every value equal to `value`, returns how many there were.
+    pub fn remove_all(&mut self, value: &T) -> usize
+    where
+        T: PartialEq,
+    {
+        self.extract_if(|other| *other == *value).count()
+    }
+
+    /// Iterates from the head, unlinking and yielding the values `filter`
+    /// returns true for. Values not reached when the iterator is dropped
+    /// stay in the list.
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            next: self.head.clone(),
+            list: self,
+            filter,
+        }
+    }
+
+    /// Removes consecutive repeated values, see [`DoublyLinkedList::dedup_by`].
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes consecutive values that map to the same key.
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&mut T) -> K,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes each value `same_bucket(value, kept)` returns true for, where
+    /// `kept` is the last value before it left in the list.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let Some(mut kept) = self.head.clone() else {
+            return;
+        };
+
+        while let Some(item) = next_of(&kept) {
+            let same = same_bucket(&mut item.borrow_mut().value, &mut kept.borrow_mut().value);
+
+            match same {
+                true => _ = self.unlink(item),
+                false => kept = item,
+            }
+        }
+    }
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            let item = self.next.take()?;
+            self.next = next_of(&item);
+
+            if (self.filter)(&mut item.borrow_mut().value) {
+                return Some(self.list.unlink(item));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.list.len))
+    }
+}
+
+impl<T, F> FusedIterator for ExtractIf<'_, T, F> where F: FnMut(&mut T) -> bool {}
+
+#[cfg(test)]
+mod tests {
+    use crate::doubly_linked_list::test_helpers::{list_of, values};
+
+    #[test]
+    fn retain_test() {
+        let mut list = list_of(&[1, 2, 3, 4, 5, 6]);
+        let three = list.handle_at(2).unwrap();
+        let four = list.handle_at(3).unwrap();
+
+        list.retain(|value| value % 2 == 0);
+        assert_eq!(values(&list), [2, 4, 6]);
+        assert_eq!(list.len(), 3);
+        assert!(!list.contains(&three) && list.contains(&four));
+        assert_eq!(list.iter().rev().map(|v| *v).collect::<Vec<_>>(), [6, 4, 2]);
+
+        list.retain(|_| false);
+        assert!(list.is_empty() && list.head().is_none() && list.tail().is_none());
+    }
+
+    #[test]
+    fn extract_if_test() {
+        let mut list = list_of(&[1, 2, 3, 4, 5, 6, 7]);
+
+        let odd: Vec<_> = list
+            .extract_if(|value| {
+                *value *= 10;
+                *value % 20 != 0
+            })
+            .collect();
+        assert_eq!(odd, [10, 30, 50, 70]);
+        assert_eq!(values(&list), [20, 40, 60]);
+
+        let mut iter = list.extract_if(|_| true);
+        assert_eq!(iter.next(), Some(20));
+        drop(iter);
+        assert_eq!(values(&list), [40, 60]);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn remove_all_test() {
+        let mut list = list_of(&[3, 1, 3, 3, 2, 3]);
+
+        assert_eq!(list.remove_all(&3), 4);
+        assert_eq!(values(&list), [1, 2]);
+        assert_eq!(list.remove_all(&3), 0);
+        assert_eq!(list.tail(), list.handle_at(1));
+    }
+
+    #[test]
+    fn dedup_test() {
+        let mut list = list_of(&[1, 1, 2, 2, 2, 1, 3, 3]);
+        list.dedup();
+        assert_eq!(values(&list), [1, 2, 1, 3]);
+
+        let mut list = list_of(&[10, 11, 25, 29, 13, 30]);
+        list.dedup_by_key(|value| *value / 10);
+        assert_eq!(values(&list), [10, 25, 13, 30]);
+        assert_eq!(list.len(), 4);
+
+        let mut list = list_of(&[1, 2, 4, 3, 5]);
+        list.dedup_by(|value, kept| value > kept);
+        assert_eq!(values(&list), [1]);
+        assert_eq!(list.tail(), list.head());
+
+        let mut list = list_of(&[]);
+        list.dedup();
+        assert!(list.is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    cell::{Ref, RefMut},
+    iter::FusedIterator,
+    marker::PhantomData,
+};
+
+use super::{next_of, prev_of, value, value_mut, DoublyLinkedList, Link};
+
+/// Values of a list, from either end.
+pub struct Iter<'a, T> {
+    front: Option<Link<T>>,
+    back: Option<Link<T>>,
+    /// Items between `front` and `back`, both included.
+    len: usize,
+    marker: PhantomData<&'a DoublyLinkedList<T>>,
+}
+
+/// Mutable values of a list, from either end.
+pub struct IterMut<'a, T> {
+    front: Option<Link<T>>,
+    back: Option<Link<T>>,
+    len: usize,
+    marker: PhantomData<&'a mut DoublyLinkedList<T>>,
+}
+
+/// Values moved out of a list, from either end.
+pub struct IntoIter<T>(DoublyLinkedList<T>);
+
+impl<T> DoublyLinkedList<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.head.clone(),
+            back: self.tail.clone(),
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.head.clone(),
+            back: self.tail.clone(),
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = Ref<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.len = self.len.checked_sub(1)?;
+        let item = self.front.take()?;
+        self.front = next_of(&item);
+
+        // SAFETY: the list is borrowed for 'a.
+        Some(unsafe { value(&item) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.len = self.len.checked_sub(1)?;
+        let item = self.back.take()?;
+        self.back = prev_of(&item);
+
+        // SAFETY: the list is borrowed for 'a.
+        Some(unsafe { value(&item) })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = RefMut<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.len = self.len.checked_sub(1)?;
+        let item = self.front.take()?;
+        self.front = next_of(&item);
+
+        // SAFETY: the list is borrowed for 'a, and every item is yielded
+        // once as `len` stops both ends when they meet.
+        Some(unsafe { value_mut(&item) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.len = self.len.checked_sub(1)?;
+        let item = self.back.take()?;
+        self.back = prev_of(&item);
+
+        // SAFETY: see `next`.
+        Some(unsafe { value_mut(&item) })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_head()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for DoublyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a DoublyLinkedList<T> {
+    type Item = Ref<'a, T>;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut DoublyLinkedList<T> {
+    type Item = RefMut<'a, T>;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doubly_linked_list::test_helpers::list_of;
+
+    #[test]
+    fn iter_test() {
+        let list = list_of(&[1, 2, 3, 4]);
+
+        assert_eq!(list.iter().map(|v| *v).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(
+            list.iter().rev().map(|v| *v).collect::<Vec<_>>(),
+            [4, 3, 2, 1]
+        );
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(*iter.next().unwrap(), 1);
+        assert_eq!(*iter.next_back().unwrap(), 4);
+        assert_eq!(*iter.next_back().unwrap(), 3);
+        assert_eq!(iter.len(), 1);
+        assert_eq!(*iter.next().unwrap(), 2);
+        assert!(iter.next().is_none() && iter.next_back().is_none());
+
+        let sum: i32 = (&list).into_iter().map(|v| *v).sum();
+        assert_eq!(sum, 10);
+        assert_eq!(DoublyLinkedList::<i32>::new().iter().len(), 0);
+    }
+
+    #[test]
+    fn iter_mut_test() {
+        let mut list = list_of(&[1, 2, 3]);
+
+        let mut iter = list.iter_mut();
+        let mut first = iter.next().unwrap();
+        let mut last = iter.next_back().unwrap();
+        *first += 10;
+        *last += 30;
+        drop((first, last));
+
+        for mut value in iter {
+            *value *= -1;
+        }
+
+        for mut value in &mut list {
+            *value += 1;
+        }
+
+        assert_eq!(list.iter().map(|v| *v).collect::<Vec<_>>(), [12, -1, 34]);
+    }
+
+    #[test]
+    fn into_iter_test() {
+        let mut iter = list_of(&[1, 2, 3, 4]).into_iter();
+
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.collect::<Vec<_>>(), [1, 2, 3]);
+
+        let list: DoublyLinkedList<String> = ["a", "b"].map(String::from).into_iter().fold(
+            DoublyLinkedList::new(),
+            |mut list, value| {
+                list.push(value);
+                list
+            },
+        );
+
+        assert_eq!(list.into_iter().rev().collect::<Vec<_>>(), ["b", "a"]);
+    }
+}
@@ -0,0 +1,173 @@
+use std::cmp::Ordering;
+
+use super::{next_of, prev_of, DoublyLinkedList, Link};
+
+impl<T> DoublyLinkedList<T> {
+    /// Stable sort, see [`DoublyLinkedList::sort_by`].
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    /// Stable sort by the keys `f` returns, which is called twice per
+    /// comparison.
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Stable bottom-up merge sort in O(n log n) time and O(1) memory. The
+    /// items are relinked rather than moved, so handles keep pointing at
+    /// their values. `compare` is only called between whole relinks, so if
+    /// it panics the list is left linked up, though partly sorted.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut width = 1;
+
+        while width < self.len {
+            let mut start = self.head.clone();
+
+            while let Some(left) = start {
+                let (Some(right), _) = skip(left.clone(), width) else {
+                    break;
+                };
+                let (end, right_len) = skip(right.clone(), width);
+
+                self.merge(left, width, right, right_len, &mut compare);
+                start = end;
+            }
+
+            width *= 2;
+        }
+    }
+
+    /// Merges the sorted run of `left_len` items from `left` with the one of
+    /// `right_len` items from `right`, which directly follows it, by moving
+    /// each item of the right run in front of the first greater one of the
+    /// left run.
+    fn merge<F>(
+        &mut self,
+        mut left: Link<T>,
+        mut left_len: usize,
+        mut right: Link<T>,
+        mut right_len: usize,
+        compare: &mut F,
+    ) where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        while left_len > 0 && right_len > 0 {
+            let order = compare(&left.borrow().value, &right.borrow().value);
+
+            if order == Ordering::Greater {
+                let next = next_of(&right);
+                self.detach(&right);
+                self.attach(prev_of(&left), Some(left.clone()), &right);
+                right_len -= 1;
+
+                match next {
+                    Some(next) => right = next,
+                    None => break,
+                }
+            } else {
+                left_len -= 1;
+
+                match next_of(&left) {
+                    Some(next) => left = next,
+                    None => break,
+                }
+            }
+        }
+    }
+}
+
+/// Walks up to `steps` items on from `item`, returns the item it stopped at
+/// and the number of items it passed.
+fn skip<T>(item: Link<T>, steps: usize) -> (Option<Link<T>>, usize) {
+    let mut current = Some(item);
+    let mut passed = 0;
+
+    while passed < steps {
+        let Some(item) = current else {
+            break;
+        };
+
+        current = next_of(&item);
+        passed += 1;
+    }
+
+    (current, passed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use super::*;
+    use crate::doubly_linked_list::test_helpers::{assert_linked, list_of, values};
+
+    #[test]
+    fn sort_test() {
+        for len in 0..40 {
+            // A spread of orders, including runs and duplicates.
+            let input: Vec<i32> = (0..len).map(|i| (i * 7919 + 13) % 17 - 8).collect();
+            let mut list = list_of(&input);
+            let mut expected = input.clone();
+            expected.sort();
+
+            list.sort();
+            assert_eq!(values(&list), expected);
+            assert_linked(&list);
+
+            list.sort_by(|a, b| b.cmp(a));
+            expected.reverse();
+            assert_eq!(values(&list), expected);
+        }
+    }
+
+    #[test]
+    fn sort_stable_test() {
+        let mut list = DoublyLinkedList::new();
+        let handles: Vec<_> = [(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (1, 'e')]
+            .into_iter()
+            .map(|pair| list.push(pair))
+            .collect();
+
+        list.sort_by_key(|(key, _)| *key);
+
+        assert_eq!(
+            values(&list),
+            [(1, 'b'), (1, 'e'), (2, 'd'), (3, 'a'), (3, 'c')]
+        );
+        assert_eq!(*list.get_node(&handles[3]).unwrap(), (2, 'd'));
+        assert_eq!(list.next(&handles[3]), Some(handles[0].clone()));
+        assert_eq!(list.head(), Some(handles[1].clone()));
+        assert_eq!(list.tail(), Some(handles[2].clone()));
+    }
+
+    #[test]
+    fn sort_panic_test() {
+        let mut list = list_of(&[5, 4, 3, 2, 1, 0]);
+        let mut calls = 0;
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                calls += 1;
+                assert!(calls < 4, "comparison failed");
+                a.cmp(b)
+            });
+        }));
+
+        assert!(result.is_err());
+        let mut left = values(&list);
+        left.sort();
+        assert_eq!(left, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(list.iter().rev().count(), 6);
+    }
+}
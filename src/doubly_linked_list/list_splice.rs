@@ -0,0 +1,196 @@
+use std::mem;
+
+use crate::error::Error;
+
+use super::{next_of, DoublyLinkedList, ListError, NodeHandle};
+
+impl<T> DoublyLinkedList<T> {
+    /// Moves all items of `other` to the end of this list in O(1), leaving
+    /// `other` empty. Handles to them stay valid.
+    pub fn append(&mut self, other: &mut DoublyLinkedList<T>) {
+        let tail = self.tail.clone();
+
+        self.splice_between(tail, None, mem::take(other));
+    }
+
+    /// Moves all items of `other` to the start of this list in O(1).
+    pub fn prepend(&mut self, other: &mut DoublyLinkedList<T>) {
+        let head = self.head.clone();
+
+        self.splice_between(None, head, mem::take(other));
+    }
+
+    /// Panics if `index > len`, see [`DoublyLinkedList::try_split_off`].
+    pub fn split_off(&mut self, index: usize) -> DoublyLinkedList<T> {
+        match self.try_split_off(index) {
+            Ok(rest) => rest,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Moves the items from `index` on into a new list in O(min(index, len -
+    /// index)), the walk to `index` from the closer end.
+    pub fn try_split_off(&mut self, index: usize) -> Result<DoublyLinkedList<T>, Error> {
+        if index > self.len {
+            return Err(ListError::OutOfBounds {
+                index,
+                len: self.len,
+            }
+            .into());
+        }
+
+        let first = self.item_at(index);
+
+        Ok(self.split_from(first))
+    }
+
+    /// Panics if `node` isn't linked into this list, see
+    /// [`DoublyLinkedList::try_split_after`].
+    pub fn split_after(&mut self, node: &NodeHandle<T>) -> DoublyLinkedList<T> {
+        match self.try_split_after(node) {
+            Ok(rest) => rest,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Moves the items after `node` into a new list. Visits the items on the
+    /// shorter side of `node` to tell them apart from the others, so this is
+    /// O(1) near either end and O(len / 2) at worst.
+    pub fn try_split_after(&mut self, node: &NodeHandle<T>) -> Result<DoublyLinkedList<T>, Error> {
+        let item = self.find(node)?;
+
+        Ok(self.split_from(next_of(&item)))
+    }
+
+    /// Panics if `node` isn't linked into this list, see
+    /// [`DoublyLinkedList::try_splice`].
+    pub fn splice(&mut self, node: &NodeHandle<T>, other: &mut DoublyLinkedList<T>) {
+        if let Err(err) = self.try_splice(node, other) {
+            panic!("{err}");
+        }
+    }
+
+    /// Moves all items of `other` after `node` in O(1), leaving `other`
+    /// empty.
+    pub fn try_splice(
+        &mut self,
+        node: &NodeHandle<T>,
+        other: &mut DoublyLinkedList<T>,
+    ) -> Result<(), Error> {
+        let item = self.find(node)?;
+        let next = next_of(&item);
+
+        self.splice_between(Some(item), next, mem::take(other));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doubly_linked_list::test_helpers::{assert_linked, list_of, values};
+
+    #[test]
+    fn append_prepend_test() {
+        let mut list = list_of(&[3, 4]);
+        let mut other = list_of(&[5, 6]);
+        let five = other.head().unwrap();
+
+        list.append(&mut other);
+        assert!(other.is_empty() && other.head().is_none());
+        assert!(list.contains(&five) && !other.contains(&five));
+
+        other.push(0);
+        let mut front = list_of(&[1, 2]);
+        list.prepend(&mut front);
+        list.append(&mut DoublyLinkedList::new());
+        other.append(&mut list);
+
+        assert_eq!(values(&other), [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(other.remove(&five), 5);
+        assert!(list.is_empty());
+        assert_linked(&other);
+        assert_linked(&list);
+    }
+
+    #[test]
+    fn split_off_test() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        let four = list.handle_at(3).unwrap();
+
+        let rest = list.split_off(3);
+        assert_eq!(values(&list), [1, 2, 3]);
+        assert_eq!(values(&rest), [4, 5]);
+        assert!(rest.contains(&four) && !list.contains(&four));
+
+        assert!(list.split_off(3).is_empty());
+        assert_eq!(values(&list.split_off(0)), [1, 2, 3]);
+        assert!(list.is_empty());
+        assert_eq!(
+            list.try_split_off(1).unwrap_err(),
+            ListError::OutOfBounds { index: 1, len: 0 }.into()
+        );
+
+        assert_linked(&list);
+        assert_linked(&rest);
+    }
+
+    #[test]
+    fn split_shorter_side_test() {
+        let mut list = list_of(&[1, 2]);
+        let mut other = list_of(&[3, 4, 5, 6]);
+        let handles: Vec<_> = list.handles().chain(other.handles()).collect();
+        list.append(&mut other);
+
+        // only the kept item is visited, the moved ones keep the identity
+        // `other` was merged into
+        let mut rest = list.split_off(1);
+        let tail = rest.split_off(4);
+
+        assert_eq!((list.len(), rest.len(), tail.len()), (1, 4, 1));
+        assert!(list.contains(&handles[0]) && !rest.contains(&handles[0]));
+        assert!(handles[1..5]
+            .iter()
+            .all(|h| rest.contains(h) && !list.contains(h)));
+        assert!(tail.contains(&handles[5]) && !rest.contains(&handles[5]));
+
+        rest.append(&mut list);
+        assert_eq!(rest.remove(&handles[0]), 1);
+        assert_eq!(values(&rest), [2, 3, 4, 5]);
+        assert_linked(&rest);
+        assert_linked(&tail);
+    }
+
+    #[test]
+    fn split_after_splice_test() {
+        let mut list = list_of(&[1, 2, 3]);
+        let mut other = list_of(&[10, 20]);
+        let one = list.head().unwrap();
+        let twenty = other.tail().unwrap();
+
+        list.splice(&one, &mut other);
+        assert_eq!(values(&list), [1, 10, 20, 2, 3]);
+        assert_eq!(list.try_splice(&twenty, &mut list_of(&[])), Ok(()));
+
+        let mut rest = list.split_after(&twenty);
+        assert_eq!(values(&list), [1, 10, 20]);
+        assert_eq!(values(&rest), [2, 3]);
+
+        assert_eq!(
+            rest.try_split_after(&twenty).unwrap_err(),
+            ListError::NotInList.into()
+        );
+        assert_eq!(
+            rest.try_splice(&one, &mut list_of(&[0])),
+            Err(ListError::NotInList.into())
+        );
+
+        rest.append(&mut list.split_after(&one));
+        assert_eq!(values(&rest), [2, 3, 10, 20]);
+        assert!(list.split_after(&one).is_empty());
+
+        assert_linked(&list);
+        assert_linked(&rest);
+    }
+}
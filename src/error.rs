@@ -0,0 +1,40 @@
+use std::fmt::Display;
+
+use crate::{doubly_linked_list::ListError, rope::rope_range::RopeError};
+
+/// Any error returned by the `try_*` operations of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Rope(RopeError),
+    List(ListError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Rope(err) => err.fmt(f),
+            Error::List(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Rope(err) => Some(err),
+            Error::List(err) => Some(err),
+        }
+    }
+}
+
+impl From<RopeError> for Error {
+    fn from(value: RopeError) -> Self {
+        Error::Rope(value)
+    }
+}
+
+impl From<ListError> for Error {
+    fn from(value: ListError) -> Self {
+        Error::List(value)
+    }
+}
@@ -1,5 +1,3 @@
-use std::usize;
-
 pub fn get_fibonacci_number(num: usize) -> usize {
     if num <= 2 {
         return 1;
@@ -1 +1,2 @@
 pub mod fibonacci_seq;
+pub mod myers_diff;
@@ -0,0 +1,288 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffTag {
+    Equal,
+    Delete,
+    Insert,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffOp {
+    pub tag: DiffTag,
+    pub old: Range<usize>,
+    pub new: Range<usize>,
+}
+
+/// Myers O((N + M) * D) diff in linear space. Returns runs of equal,
+/// deleted and inserted elements covering both sequences in order.
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<DiffOp> {
+    let max = (old.len() + new.len()).div_ceil(2) + 1;
+    let mut forward = vec![0; 2 * max + 1];
+    let mut backward = vec![0; 2 * max + 1];
+    let mut ops = vec![];
+
+    conquer(
+        old,
+        0..old.len(),
+        new,
+        0..new.len(),
+        &mut forward,
+        &mut backward,
+        &mut ops,
+    );
+
+    ops
+}
+
+/// Diffs `old[old_range]` against `new[new_range]` by splitting both at a
+/// middle snake and recursing into the halves, which keeps only the two
+/// furthest reaching paths in memory instead of one per edit distance.
+fn conquer<T: PartialEq>(
+    old: &[T],
+    mut old_range: Range<usize>,
+    new: &[T],
+    mut new_range: Range<usize>,
+    forward: &mut [usize],
+    backward: &mut [usize],
+    ops: &mut Vec<DiffOp>,
+) {
+    let prefix = common_prefix(&old[old_range.clone()], &new[new_range.clone()]);
+    push(
+        ops,
+        DiffTag::Equal,
+        old_range.start,
+        new_range.start,
+        prefix,
+        prefix,
+    );
+    old_range.start += prefix;
+    new_range.start += prefix;
+
+    let suffix = common_suffix(&old[old_range.clone()], &new[new_range.clone()]);
+    old_range.end -= suffix;
+    new_range.end -= suffix;
+
+    match (old_range.is_empty(), new_range.is_empty()) {
+        (true, true) => (),
+        (false, true) => push(
+            ops,
+            DiffTag::Delete,
+            old_range.start,
+            new_range.start,
+            old_range.len(),
+            0,
+        ),
+        (true, false) => push(
+            ops,
+            DiffTag::Insert,
+            old_range.start,
+            new_range.start,
+            0,
+            new_range.len(),
+        ),
+        (false, false) => {
+            let (x, y) = middle_snake(
+                old,
+                old_range.clone(),
+                new,
+                new_range.clone(),
+                forward,
+                backward,
+            );
+
+            conquer(
+                old,
+                old_range.start..x,
+                new,
+                new_range.start..y,
+                forward,
+                backward,
+                ops,
+            );
+            conquer(
+                old,
+                x..old_range.end,
+                new,
+                y..new_range.end,
+                forward,
+                backward,
+                ops,
+            );
+        }
+    }
+
+    push(
+        ops,
+        DiffTag::Equal,
+        old_range.end,
+        new_range.end,
+        suffix,
+        suffix,
+    );
+}
+
+/// Runs the search from both corners until the paths overlap, and returns
+/// a point on an optimal path strictly between the corners. The ranges
+/// have to be non-empty and differ at both ends. `forward[max + k]` holds
+/// the furthest x reached on diagonal `k`, `backward` the same counted from
+/// the ends.
+fn middle_snake<T: PartialEq>(
+    old: &[T],
+    old_range: Range<usize>,
+    new: &[T],
+    new_range: Range<usize>,
+    forward: &mut [usize],
+    backward: &mut [usize],
+) -> (usize, usize) {
+    let n = old_range.len() as isize;
+    let m = new_range.len() as isize;
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+    let max = (forward.len() / 2) as isize;
+    let at = |k: isize| (max + k) as usize;
+
+    forward[at(1)] = 0;
+    backward[at(1)] = 0;
+
+    for d in 0..=(n + m + 1) / 2 {
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = match k == -d || (k != d && forward[at(k - 1)] < forward[at(k + 1)]) {
+                true => forward[at(k + 1)] as isize,
+                false => forward[at(k - 1)] as isize + 1,
+            };
+            let (x0, y0) = (x, x - k);
+
+            if x < n && y0 < m {
+                x += common_prefix(
+                    &old[old_range.start + x as usize..old_range.end],
+                    &new[new_range.start + y0 as usize..new_range.end],
+                ) as isize;
+            }
+
+            forward[at(k)] = x as usize;
+
+            if odd && (k - delta).abs() < d && x + backward[at(delta - k)] as isize >= n {
+                return (old_range.start + x0 as usize, new_range.start + y0 as usize);
+            }
+        }
+
+        // ascending, so that on ties deletions end up in front of insertions
+        for k in (-d..=d).step_by(2) {
+            let mut x = match k == -d || (k != d && backward[at(k - 1)] < backward[at(k + 1)]) {
+                true => backward[at(k + 1)] as isize,
+                false => backward[at(k - 1)] as isize + 1,
+            };
+            let mut y = x - k;
+
+            if x < n && y < m {
+                let suffix = common_suffix(
+                    &old[old_range.start..old_range.end - x as usize],
+                    &new[new_range.start..new_range.end - y as usize],
+                ) as isize;
+                x += suffix;
+                y += suffix;
+            }
+
+            backward[at(k)] = x as usize;
+
+            if !odd && (k - delta).abs() <= d && x + forward[at(delta - k)] as isize >= n {
+                return (old_range.end - x as usize, new_range.end - y as usize);
+            }
+        }
+    }
+
+    unreachable!("the paths overlap within (n + m + 1) / 2 edits")
+}
+
+fn common_prefix<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+fn common_suffix<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// Appends a run, merging it into the last one if that has the same tag.
+fn push(ops: &mut Vec<DiffOp>, tag: DiffTag, x: usize, y: usize, old_len: usize, new_len: usize) {
+    if old_len == 0 && new_len == 0 {
+        return;
+    }
+
+    match ops.last_mut() {
+        Some(op) if op.tag == tag => {
+            op.old.end += old_len;
+            op.new.end += new_len;
+        }
+        _ => ops.push(DiffOp {
+            tag,
+            old: x..x + old_len,
+            new: y..y + new_len,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Longest common subsequence by dynamic programming.
+    fn lcs(a: &[u8], b: &[u8]) -> usize {
+        let mut row = vec![0; b.len() + 1];
+
+        for x in a {
+            let mut diagonal = 0;
+
+            for (j, y) in b.iter().enumerate() {
+                let above = row[j + 1];
+                row[j + 1] = match x == y {
+                    true => diagonal + 1,
+                    false => row[j].max(above),
+                };
+                diagonal = above;
+            }
+        }
+
+        row[b.len()]
+    }
+
+    #[test]
+    fn diff_minimal_test() {
+        let mut seed = 7_u32;
+        let mut next = |bound: u32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 16) % bound
+        };
+
+        for _ in 0..300 {
+            let old: Vec<u8> = (0..next(12)).map(|_| b'a' + next(3) as u8).collect();
+            let new: Vec<u8> = (0..next(12)).map(|_| b'a' + next(3) as u8).collect();
+
+            let ops = diff(&old, &new);
+            let (mut x, mut y, mut edits) = (0, 0, 0);
+
+            for op in &ops {
+                assert_eq!((op.old.start, op.new.start), (x, y));
+
+                match op.tag {
+                    DiffTag::Equal => assert_eq!(old[op.old.clone()], new[op.new.clone()]),
+                    DiffTag::Delete => assert!(op.new.is_empty()),
+                    DiffTag::Insert => assert!(op.old.is_empty()),
+                }
+
+                if op.tag != DiffTag::Equal {
+                    edits += op.old.len() + op.new.len();
+                }
+
+                (x, y) = (op.old.end, op.new.end);
+            }
+
+            assert_eq!((x, y), (old.len(), new.len()));
+            assert_eq!(edits, old.len() + new.len() - 2 * lcs(&old, &new));
+        }
+    }
+}
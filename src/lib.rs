@@ -1,3 +1,5 @@
 pub mod doubly_linked_list;
+pub mod error;
 pub mod helpers;
+pub mod raw_doubly_linked_list;
 pub mod rope;
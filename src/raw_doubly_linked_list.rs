@@ -0,0 +1,485 @@
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{doubly_linked_list::ListError, error::Error};
+
+static NEXT_LIST_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Doubly linked list over raw pointers. Items are owned boxes linked with
+/// `NonNull`, so there is no reference counting and no runtime borrow
+/// checking. Handles name a slot of the list instead of pointing at the
+/// item, so a stale or foreign handle is rejected instead of dereferenced.
+pub struct RawDoublyLinkedList<T> {
+    head: Option<NonNull<RawListItem<T>>>,
+    tail: Option<NonNull<RawListItem<T>>>,
+    len: usize,
+    /// Items by slot, a slot keeps its index while the item is linked.
+    slots: Vec<Slot<T>>,
+    /// Slots without an item, reused before new ones are pushed.
+    free: Vec<usize>,
+    id: usize,
+    /// The list owns its items.
+    marker: PhantomData<Box<RawListItem<T>>>,
+}
+
+struct RawListItem<T> {
+    next: Option<NonNull<RawListItem<T>>>,
+    prev: Option<NonNull<RawListItem<T>>>,
+    slot: usize,
+    value: T,
+}
+
+struct Slot<T> {
+    item: Option<NonNull<RawListItem<T>>>,
+    /// Bumped whenever the item is removed, so handles to it go stale.
+    generation: usize,
+}
+
+/// Names an item of a [`RawDoublyLinkedList`] by the list, the slot and the
+/// generation of the slot. Lists reject it once the item is removed or if
+/// it was handed out by another list.
+pub struct RawHandle<T> {
+    list: usize,
+    slot: usize,
+    generation: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for RawHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RawHandle<T> {}
+
+impl<T> PartialEq for RawHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.list, self.slot, self.generation) == (other.list, other.slot, other.generation)
+    }
+}
+
+impl<T> Eq for RawHandle<T> {}
+
+impl<T> Debug for RawHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RawHandle")
+            .field("list", &self.list)
+            .field("slot", &self.slot)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+// `NonNull` opts out of both, but the items are owned by the list alone,
+// like the nodes of a `Box`-based list, and are only reached through
+// `&self` or `&mut self`. Handles hold no pointers.
+unsafe impl<T: Send> Send for RawDoublyLinkedList<T> {}
+unsafe impl<T: Sync> Sync for RawDoublyLinkedList<T> {}
+
+impl<T> Default for RawDoublyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RawDoublyLinkedList<T> {
+    pub fn new() -> Self {
+        RawDoublyLinkedList {
+            head: None,
+            tail: None,
+            len: 0,
+            slots: vec![],
+            free: vec![],
+            id: NEXT_LIST_ID.fetch_add(1, Ordering::Relaxed),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn head(&self) -> Option<RawHandle<T>> {
+        self.head.map(|head| self.handle(head))
+    }
+
+    pub fn tail(&self) -> Option<RawHandle<T>> {
+        self.tail.map(|tail| self.handle(tail))
+    }
+
+    pub fn push_head(&mut self, value: T) -> RawHandle<T> {
+        let item = self.link(None, self.head, value);
+
+        self.handle(item)
+    }
+
+    pub fn push(&mut self, value: T) -> RawHandle<T> {
+        let item = self.link(self.tail, None, value);
+
+        self.handle(item)
+    }
+
+    pub fn pop_head(&mut self) -> Option<T> {
+        // SAFETY: the head is linked into this list.
+        self.head.map(|head| unsafe { self.unlink(head) })
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        // SAFETY: the tail is linked into this list.
+        self.tail.map(|tail| unsafe { self.unlink(tail) })
+    }
+
+    pub fn peek_head(&self) -> Option<&T> {
+        // SAFETY: the head is linked, and so alive, while `self` is borrowed.
+        self.head.map(|head| unsafe { &(*head.as_ptr()).value })
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        // SAFETY: the tail is linked, and so alive, while `self` is borrowed.
+        self.tail.map(|tail| unsafe { &(*tail.as_ptr()).value })
+    }
+
+    pub fn contains(&self, node: RawHandle<T>) -> bool {
+        self.find(node).is_ok()
+    }
+
+    /// Panics if `node` isn't linked into this list, see
+    /// [`RawDoublyLinkedList::try_insert_after`].
+    pub fn insert_after(&mut self, node: RawHandle<T>, value: T) -> RawHandle<T> {
+        match self.try_insert_after(node, value) {
+            Ok(handle) => handle,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_insert_after(
+        &mut self,
+        node: RawHandle<T>,
+        value: T,
+    ) -> Result<RawHandle<T>, Error> {
+        let item = self.find(node)?;
+        // SAFETY: `find` only returns items linked into this list.
+        let next = unsafe { (*item.as_ptr()).next };
+        let item = self.link(Some(item), next, value);
+
+        Ok(self.handle(item))
+    }
+
+    /// Panics if `node` isn't linked into this list, see
+    /// [`RawDoublyLinkedList::try_insert_before`].
+    pub fn insert_before(&mut self, node: RawHandle<T>, value: T) -> RawHandle<T> {
+        match self.try_insert_before(node, value) {
+            Ok(handle) => handle,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_insert_before(
+        &mut self,
+        node: RawHandle<T>,
+        value: T,
+    ) -> Result<RawHandle<T>, Error> {
+        let item = self.find(node)?;
+        // SAFETY: `find` only returns items linked into this list.
+        let prev = unsafe { (*item.as_ptr()).prev };
+        let item = self.link(prev, Some(item), value);
+
+        Ok(self.handle(item))
+    }
+
+    /// Panics if `node` isn't linked into this list, see
+    /// [`RawDoublyLinkedList::try_remove`].
+    pub fn remove(&mut self, node: RawHandle<T>) -> T {
+        match self.try_remove(node) {
+            Ok(value) => value,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Unlinks `node` and moves its value out. Handles to it are rejected
+    /// afterwards with [`ListError::NotInList`].
+    pub fn try_remove(&mut self, node: RawHandle<T>) -> Result<T, Error> {
+        let item = self.find(node)?;
+
+        // SAFETY: `find` only returns items linked into this list.
+        Ok(unsafe { self.unlink(item) })
+    }
+
+    /// Value of `node`, `None` if it isn't linked into this list.
+    pub fn get(&self, node: RawHandle<T>) -> Option<&T> {
+        let item = self.find(node).ok()?;
+
+        // SAFETY: the item is linked, and so alive, while `self` is borrowed.
+        Some(unsafe { &(*item.as_ptr()).value })
+    }
+
+    pub fn get_mut(&mut self, node: RawHandle<T>) -> Option<&mut T> {
+        let item = self.find(node).ok()?;
+
+        // SAFETY: the item is linked and `self` is borrowed mutably, so
+        // nothing else reaches it.
+        Some(unsafe { &mut (*item.as_ptr()).value })
+    }
+
+    pub fn iter(&self) -> RawDoublyLinkedListIter<'_, T> {
+        RawDoublyLinkedListIter {
+            next: self.head,
+            marker: PhantomData,
+        }
+    }
+
+    /// Resolves `node` to its item if it is linked into this list.
+    fn find(&self, node: RawHandle<T>) -> Result<NonNull<RawListItem<T>>, ListError> {
+        match self.slots.get(node.slot) {
+            Some(&Slot {
+                item: Some(item),
+                generation,
+            }) if node.list == self.id && node.generation == generation => Ok(item),
+            _ => Err(ListError::NotInList),
+        }
+    }
+
+    fn handle(&self, item: NonNull<RawListItem<T>>) -> RawHandle<T> {
+        // SAFETY: callers pass items linked into this list.
+        let slot = unsafe { (*item.as_ptr()).slot };
+
+        RawHandle {
+            list: self.id,
+            slot,
+            generation: self.slots[slot].generation,
+            marker: PhantomData,
+        }
+    }
+
+    /// Allocates an item between `prev` and `next`, which have to be
+    /// neighbours in this list or its ends.
+    fn link(
+        &mut self,
+        prev: Option<NonNull<RawListItem<T>>>,
+        next: Option<NonNull<RawListItem<T>>>,
+        value: T,
+    ) -> NonNull<RawListItem<T>> {
+        let slot = self.free.pop().unwrap_or_else(|| {
+            self.slots.push(Slot {
+                item: None,
+                generation: 0,
+            });
+
+            self.slots.len() - 1
+        });
+        let item = NonNull::from(Box::leak(Box::new(RawListItem {
+            next,
+            prev,
+            slot,
+            value,
+        })));
+
+        self.slots[slot].item = Some(item);
+
+        // SAFETY: `prev` and `next` are linked into this list and nothing
+        // else borrows them while `self` is borrowed mutably.
+        unsafe {
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(item),
+                None => self.head = Some(item),
+            }
+
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(item),
+                None => self.tail = Some(item),
+            }
+        }
+
+        self.len += 1;
+
+        item
+    }
+
+    /// # Safety
+    ///
+    /// `item` has to be linked into this list.
+    unsafe fn unlink(&mut self, item: NonNull<RawListItem<T>>) -> T {
+        // SAFETY: linked items were leaked from a box by `link` and are
+        // freed only here, after they are no longer reachable.
+        let item = unsafe { Box::from_raw(item.as_ptr()) };
+        let slot = &mut self.slots[item.slot];
+
+        slot.item = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(item.slot);
+
+        unsafe {
+            match item.prev {
+                Some(prev) => (*prev.as_ptr()).next = item.next,
+                None => self.head = item.next,
+            }
+
+            match item.next {
+                Some(next) => (*next.as_ptr()).prev = item.prev,
+                None => self.tail = item.prev,
+            }
+        }
+
+        self.len -= 1;
+
+        item.value
+    }
+}
+
+impl<T> Drop for RawDoublyLinkedList<T> {
+    fn drop(&mut self) {
+        while self.pop_head().is_some() {}
+    }
+}
+
+impl<T: Debug> Debug for RawDoublyLinkedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+pub struct RawDoublyLinkedListIter<'a, T> {
+    next: Option<NonNull<RawListItem<T>>>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for RawDoublyLinkedListIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.next?;
+
+        // SAFETY: the list is borrowed for 'a, so its items stay linked.
+        unsafe {
+            self.next = (*item.as_ptr()).next;
+
+            Some(&(*item.as_ptr()).value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn push_pop_test() {
+        let mut list = RawDoublyLinkedList::new();
+        list.push(5);
+        list.push(25);
+        list.push_head(1);
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.peek_head(), Some(&1));
+        assert_eq!(list.peek(), Some(&25));
+        assert_eq!(list.pop(), Some(25));
+        assert_eq!(list.pop_head(), Some(1));
+        assert_eq!(list.pop_head(), Some(5));
+        assert_eq!(list.pop(), None);
+        assert_eq!(list.pop_head(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn insert_remove_test() {
+        let mut list = RawDoublyLinkedList::new();
+        let first = list.push(5);
+        let last = list.push(50);
+
+        let middle = list.insert_after(first, 25);
+        list.insert_before(first, 1);
+        list.insert_after(last, 100);
+        *list.get_mut(middle).unwrap() += 1;
+
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            [1, 5, 26, 50, 100]
+        );
+
+        assert_eq!(list.remove(first), 5);
+        assert_eq!(list.remove(last), 50);
+        assert_eq!(list.get(middle), Some(&26));
+
+        assert_eq!(format!("{list:?}"), "[1, 26, 100]");
+        assert_eq!(list.head().and_then(|h| list.get(h)), Some(&1));
+        assert_eq!(list.tail().and_then(|t| list.get(t)), Some(&100));
+
+        list.remove(list.head().unwrap());
+        list.remove(list.head().unwrap());
+        list.remove(list.tail().unwrap());
+
+        assert!(list.head().is_none() && list.tail().is_none());
+    }
+
+    #[test]
+    fn stale_handle_test() {
+        let mut list = RawDoublyLinkedList::new();
+        let mut other = RawDoublyLinkedList::new();
+        let removed = list.push(1);
+        let foreign = other.push(2);
+
+        list.remove(removed);
+        // reuses the slot of `removed`
+        let reused = list.push(3);
+
+        assert!(!list.contains(removed) && list.contains(reused));
+        assert_eq!(list.get(removed), None);
+        assert_eq!(list.get_mut(foreign), None);
+        assert_eq!(list.try_remove(removed), Err(ListError::NotInList.into()));
+        assert_eq!(
+            list.try_insert_after(foreign, 4),
+            Err(ListError::NotInList.into())
+        );
+        assert_eq!(
+            list.try_insert_before(removed, 4),
+            Err(ListError::NotInList.into())
+        );
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [3]);
+        assert_eq!(other.remove(foreign), 2);
+    }
+
+    #[test]
+    fn drop_test() {
+        let live = Rc::new(Cell::new(0));
+
+        struct Counted(Rc<Cell<i32>>);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() - 1);
+            }
+        }
+
+        {
+            let mut list = RawDoublyLinkedList::new();
+
+            for _ in 0..10 {
+                live.set(live.get() + 1);
+                list.push(Counted(Rc::clone(&live)));
+            }
+
+            drop(list.pop());
+            assert_eq!(live.get(), 9);
+        }
+
+        assert_eq!(live.get(), 0);
+
+        let mut list = RawDoublyLinkedList::new();
+        // Miri interprets every push, keep it to a reasonable length there.
+        let len = if cfg!(miri) { 1_000 } else { 1_000_000 };
+
+        for i in 0..len {
+            list.push(i);
+        }
+    }
+}
@@ -1,3 +1,19 @@
+#[allow(clippy::module_inception)]
 pub mod rope;
+pub mod rope_anchor;
+pub mod rope_attributes;
+pub mod rope_chunk;
+pub mod rope_diff;
+pub mod rope_edit;
 pub mod rope_iter;
+pub mod rope_merge;
 pub mod rope_node;
+pub mod rope_par;
+pub mod rope_range;
+pub mod rope_render;
+#[cfg(feature = "serde")]
+pub mod rope_serde;
+pub mod rope_snapshot;
+pub mod rope_traits;
+pub mod rope_validate;
+pub mod rope_words;
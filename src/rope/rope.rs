@@ -1,44 +1,90 @@
-use std::{fmt::Display, rc::Rc};
+use std::{fmt::Display, sync::Arc};
 
 use super::{
+    rope_anchor::AnchorSet,
+    rope_chunk::Chunk,
     rope_iter::RopeIter,
     rope_node::{Node, RopeNode, RopeNodeType},
 };
 
 #[derive(Debug)]
-pub struct Rope {
-    root: RopeNode,
+pub struct Rope<T = String> {
+    pub(crate) root: RopeNode<T>,
+    pub(crate) anchors: AnchorSet,
 }
 
-impl Rope {
-    pub fn new(node: RopeNode) -> Self {
+impl<T: Chunk> Rope<T> {
+    pub fn new(node: RopeNode<T>) -> Self {
         let root = match node.0.as_ref() {
-            RopeNodeType::Leaf(leaf) => Rc::new(RopeNodeType::Node(Node {
-                left: Rc::clone(&node.0).into(),
-                right: Rc::new(RopeNodeType::None).into(),
+            RopeNodeType::Leaf(leaf) => Arc::new(RopeNodeType::Node(Node {
+                left: Arc::clone(&node.0).into(),
+                right: Arc::new(RopeNodeType::None).into(),
                 weight: leaf.value.len(),
             }))
             .into(),
             RopeNodeType::Node(_) | RopeNodeType::None => node,
         };
 
-        Self { root }
+        Self {
+            root,
+            anchors: AnchorSet::default(),
+        }
     }
 
-    pub fn get_char(&self, index: usize) -> Option<char> {
-        self.root.get_char(index)
+    /// Element at `index`, see [`RopeNode::get`].
+    pub fn get(&self, index: usize) -> Option<&T::Item> {
+        self.root.get(index)
     }
 
-    pub fn iter(&self) -> RopeIter {
+    pub fn iter(&self) -> RopeIter<T> {
         self.root.iter()
     }
 
-    pub fn split(&self, index: usize) -> (RopeNode, RopeNode) {
+    /// Splits the tree at `index`, see [`Rope::try_split`].
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not a char boundary.
+    pub fn split(&self, index: usize) -> (RopeNode<T>, RopeNode<T>) {
         self.root.split(index)
     }
 
+    /// Truncates the rope to the `len` elements from `start`.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds or doesn't start and end on a char
+    /// boundary, see [`Rope::try_substring`].
     pub fn substring(&mut self, start: usize, len: usize) {
-        self.root = self.root.substring(start, len);
+        let range = self.root.expect_range(start, len);
+
+        if !self.anchors.is_empty() {
+            let total = self.root.measure();
+
+            self.anchors.delete_text(range.end, total - range.end);
+            self.anchors.delete_text(0, range.start);
+        }
+
+        self.root = self.root.substring(range.start, range.len());
+        self.debug_validate();
+    }
+
+    /// Truncates the rope to `index` and returns the rest, together with the
+    /// anchors pointing into it.
+    pub fn split_off(&mut self, index: usize) -> Rope<T> {
+        let index = index.min(self.len());
+        let (left, right) = self.root.split(index);
+
+        self.root = left;
+        self.debug_validate();
+
+        let other = Rope {
+            root: right,
+            anchors: self.anchors.split_off(index),
+        };
+        other.debug_validate();
+
+        other
     }
 
     pub fn rebalance(&mut self) {
@@ -47,6 +93,7 @@ impl Rope {
         }
 
         self.root = self.root.rebalance();
+        self.debug_validate();
     }
 
     pub fn is_balanced(&self) -> bool {
@@ -57,27 +104,79 @@ impl Rope {
         self.root.len()
     }
 
-    pub fn insert(&mut self, index: usize, value: String) {
+    pub fn is_empty(&self) -> bool {
+        self.root.is_empty()
+    }
+
+    pub fn insert(&mut self, index: usize, value: T) {
+        if value.is_empty() {
+            return;
+        }
+
+        if !self.anchors.is_empty() {
+            self.anchors.insert_text(index.min(self.len()), value.len());
+        }
+
         self.root = self.root.insert(index, value);
         self.rebalance();
+        self.debug_validate();
     }
 
+    /// Removes the `len` elements from `start`.
+    ///
+    /// # Panics
+    ///
+    /// Like [`Rope::substring`], see [`Rope::try_delete`].
     pub fn delete(&mut self, start: usize, len: usize) {
+        let range = self.root.expect_range(start, len);
+
+        if !self.anchors.is_empty() {
+            self.anchors.delete_text(range.start, range.len());
+        }
+
         self.root = self.root.delete(start, len);
+        self.rebalance();
+        self.debug_validate();
+    }
+}
+
+impl Rope {
+    pub fn get_char(&self, index: usize) -> Option<char> {
+        self.root.get_char(index)
     }
 }
 
-impl FromIterator<RopeNode> for Rope {
-    fn from_iter<T: IntoIterator<Item = RopeNode>>(iter: T) -> Self {
+impl<T: Chunk> FromIterator<RopeNode<T>> for Rope<T> {
+    fn from_iter<I: IntoIterator<Item = RopeNode<T>>>(iter: I) -> Self {
         Rope {
             root: RopeNode::from_iter(iter),
+            anchors: AnchorSet::default(),
         }
     }
 }
 
-impl Display for Rope {
+/// Writes the content, see [`Rope::to_ascii_tree`] for the structure.
+impl<T: Chunk + Display> Display for Rope<T> {
+    /// Writes the leafs one by one. Width and precision apply to the whole
+    /// text, which is collected into a string first to pad it once.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.root.0.fmt(f)
+        let padded = f.width().is_some() || f.precision().is_some();
+        let mut text = String::new();
+
+        for node in self.iter() {
+            if let Some(leaf) = node.map_leaf() {
+                match padded {
+                    true => text.push_str(&leaf.value.to_string()),
+                    false => write!(f, "{}", leaf.value)?,
+                }
+            }
+        }
+
+        if !padded {
+            return Ok(());
+        }
+
+        f.pad(&text)
     }
 }
 
@@ -89,25 +188,25 @@ mod tests {
     #[test]
     fn traverse_test() {
         let rope = Rope {
-            root: Rc::new(RopeNodeType::Node(Node {
-                left: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Leaf(Leaf {
+            root: Arc::new(RopeNodeType::Node(Node {
+                left: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("hello"),
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("world"),
                     }))
                     .into(),
                     weight: 5,
                 }))
                 .into(),
-                right: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Leaf(Leaf {
+                right: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("My name"),
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("is sugondese"),
                     }))
                     .into(),
@@ -117,6 +216,7 @@ mod tests {
                 weight: 5,
             }))
             .into(),
+            anchors: AnchorSet::default(),
         };
 
         let mut iter = rope.iter();
@@ -133,25 +233,25 @@ mod tests {
     #[test]
     fn get_char_test() {
         let rope = Rope {
-            root: Rc::new(RopeNodeType::Node(Node {
-                left: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Leaf(Leaf {
+            root: Arc::new(RopeNodeType::Node(Node {
+                left: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("hello "),
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("world! "),
                     }))
                     .into(),
                     weight: 6,
                 }))
                 .into(),
-                right: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Leaf(Leaf {
+                right: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("My name"),
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("is sugondese"),
                     }))
                     .into(),
@@ -161,6 +261,7 @@ mod tests {
                 weight: 13,
             }))
             .into(),
+            anchors: AnchorSet::default(),
         };
 
         assert_eq!(rope.get_char(4).unwrap(), 'o');
@@ -175,12 +276,12 @@ mod tests {
 
     #[test]
     fn concat_test() {
-        let node1: RopeNode = Rc::new(RopeNodeType::Node(Node {
-            left: Rc::new(RopeNodeType::Leaf(Leaf {
+        let node1: RopeNode = Arc::new(RopeNodeType::Node(Node {
+            left: Arc::new(RopeNodeType::Leaf(Leaf {
                 value: String::from("hello "),
             }))
             .into(),
-            right: Rc::new(RopeNodeType::Leaf(Leaf {
+            right: Arc::new(RopeNodeType::Leaf(Leaf {
                 value: String::from("world! "),
             }))
             .into(),
@@ -188,12 +289,12 @@ mod tests {
         }))
         .into();
 
-        let node2 = Rc::new(RopeNodeType::Node(Node {
-            left: Rc::new(RopeNodeType::Leaf(Leaf {
+        let node2 = Arc::new(RopeNodeType::Node(Node {
+            left: Arc::new(RopeNodeType::Leaf(Leaf {
                 value: String::from("My name"),
             }))
             .into(),
-            right: Rc::new(RopeNodeType::Leaf(Leaf {
+            right: Arc::new(RopeNodeType::Leaf(Leaf {
                 value: String::from("is sugondese"),
             }))
             .into(),
@@ -217,26 +318,26 @@ mod tests {
     #[test]
     fn substring_test() {
         let mut rope = Rope {
-            root: Rc::new(RopeNodeType::Node(Node {
-                left: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Node(Node {
-                        left: Rc::new(RopeNodeType::Leaf(Leaf {
+            root: Arc::new(RopeNodeType::Node(Node {
+                left: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Node(Node {
+                        left: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("hello "),
                         }))
                         .into(),
-                        right: Rc::new(RopeNodeType::Leaf(Leaf {
+                        right: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("world! "),
                         }))
                         .into(),
                         weight: 6,
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Node(Node {
-                        left: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Node(Node {
+                        left: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("My name"),
                         }))
                         .into(),
-                        right: Rc::new(RopeNodeType::Leaf(Leaf {
+                        right: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("is sugondese"),
                         }))
                         .into(),
@@ -246,25 +347,25 @@ mod tests {
                     weight: 13,
                 }))
                 .into(),
-                right: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Node(Node {
-                        left: Rc::new(RopeNodeType::Leaf(Leaf {
+                right: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Node(Node {
+                        left: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("hello "),
                         }))
                         .into(),
-                        right: Rc::new(RopeNodeType::Leaf(Leaf {
+                        right: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("world! "),
                         }))
                         .into(),
                         weight: 6,
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Node(Node {
-                        left: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Node(Node {
+                        left: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("My name"),
                         }))
                         .into(),
-                        right: Rc::new(RopeNodeType::Leaf(Leaf {
+                        right: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("is sugondese"),
                         }))
                         .into(),
@@ -274,40 +375,41 @@ mod tests {
                     weight: 13,
                 }))
                 .into(),
-                weight: 1,
+                weight: 32,
             }))
             .into(),
+            anchors: AnchorSet::default(),
         };
 
         rope.substring(3, 40);
 
         let expected = r#"Node(Left: Node(Left: Node(Left: Leaf("lo "), Right: Leaf("world! ")), Right: Node(Left: Leaf("My name"), Right: Leaf("is sugondese"))), Right: Node(Left: Leaf("hello "), Right: Leaf("world")))"#;
 
-        assert_eq!(expected, format!("{rope}"));
+        assert_eq!(expected, format!("{}", rope.root));
     }
 
     #[test]
     fn get_depth_test() {
-        let node: RopeNode = Rc::new(RopeNodeType::Node(Node {
-            left: Rc::new(RopeNodeType::Node(Node {
-                left: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Leaf(Leaf {
+        let node: RopeNode = Arc::new(RopeNodeType::Node(Node {
+            left: Arc::new(RopeNodeType::Node(Node {
+                left: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("hello "),
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("world! "),
                     }))
                     .into(),
                     weight: 6,
                 }))
                 .into(),
-                right: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Leaf(Leaf {
+                right: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("My name"),
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("is sugondese"),
                     }))
                     .into(),
@@ -317,7 +419,7 @@ mod tests {
                 weight: 13,
             }))
             .into(),
-            right: Rc::new(RopeNodeType::None).into(),
+            right: Arc::new(RopeNodeType::None).into(),
             weight: 0,
         }))
         .into();
@@ -328,26 +430,26 @@ mod tests {
     #[test]
     fn rebalance_test() {
         let mut rope = Rope::new(
-            Rc::new(RopeNodeType::Node(Node {
-                left: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Node(Node {
-                        left: Rc::new(RopeNodeType::Leaf(Leaf {
+            Arc::new(RopeNodeType::Node(Node {
+                left: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Node(Node {
+                        left: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("hello "),
                         }))
                         .into(),
-                        right: Rc::new(RopeNodeType::Leaf(Leaf {
+                        right: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("world! "),
                         }))
                         .into(),
                         weight: 6,
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Node(Node {
-                        left: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Node(Node {
+                        left: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("My name"),
                         }))
                         .into(),
-                        right: Rc::new(RopeNodeType::Leaf(Leaf {
+                        right: Arc::new(RopeNodeType::Leaf(Leaf {
                             value: String::from("is sugondese"),
                         }))
                         .into(),
@@ -357,7 +459,7 @@ mod tests {
                     weight: 13,
                 }))
                 .into(),
-                right: Rc::new(RopeNodeType::None).into(),
+                right: Arc::new(RopeNodeType::None).into(),
                 weight: 0,
             }))
             .into(),
@@ -367,26 +469,26 @@ mod tests {
 
         let expected = r#"Node(Left: Node(Left: Leaf("hello "), Right: Leaf("world! ")), Right: Node(Left: Leaf("My name"), Right: Leaf("is sugondese")))"#;
 
-        assert_eq!(expected, format!("{rope}"));
+        assert_eq!(expected, format!("{}", rope.root));
     }
 
     #[test]
     fn split_test() {
         let rope = Rope::new(
-            Rc::new(RopeNodeType::Node(Node {
-                left: Rc::new(RopeNodeType::Node(Node {
-                    left: Rc::new(RopeNodeType::Leaf(Leaf {
+            Arc::new(RopeNodeType::Node(Node {
+                left: Arc::new(RopeNodeType::Node(Node {
+                    left: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("hello "),
                     }))
                     .into(),
-                    right: Rc::new(RopeNodeType::Leaf(Leaf {
+                    right: Arc::new(RopeNodeType::Leaf(Leaf {
                         value: String::from("world! "),
                     }))
                     .into(),
                     weight: 6,
                 }))
                 .into(),
-                right: Rc::new(RopeNodeType::None).into(),
+                right: Arc::new(RopeNodeType::None).into(),
                 weight: 13,
             }))
             .into(),
@@ -425,20 +527,20 @@ mod tests {
 
     #[test]
     fn insert_test() {
-        let root_node: RopeNode = Rc::new(RopeNodeType::Node(Node {
-            left: Rc::new(RopeNodeType::Node(Node {
-                left: Rc::new(RopeNodeType::Leaf(Leaf {
+        let root_node: RopeNode = Arc::new(RopeNodeType::Node(Node {
+            left: Arc::new(RopeNodeType::Node(Node {
+                left: Arc::new(RopeNodeType::Leaf(Leaf {
                     value: String::from("hello "),
                 }))
                 .into(),
-                right: Rc::new(RopeNodeType::Leaf(Leaf {
+                right: Arc::new(RopeNodeType::Leaf(Leaf {
                     value: String::from("world! "),
                 }))
                 .into(),
                 weight: 6,
             }))
             .into(),
-            right: Rc::new(RopeNodeType::None).into(),
+            right: Arc::new(RopeNodeType::None).into(),
             weight: 13,
         }))
         .into();
@@ -467,7 +569,7 @@ mod tests {
         ];
 
         for (idx, exp_result) in expected_result {
-            let mut rope = Rope::new(Rc::clone(&root_node.0).into());
+            let mut rope = Rope::new(Arc::clone(&root_node.0).into());
             rope.insert(idx, String::from("new_leaf"));
             assert_eq!(exp_result, format!("{}", rope.root));
         }
@@ -475,42 +577,32 @@ mod tests {
 
     #[test]
     fn delete_test() {
-        let root_node: RopeNode = Rc::new(RopeNodeType::Node(Node {
-            left: Rc::new(RopeNodeType::Node(Node {
-                left: Rc::new(RopeNodeType::Leaf(Leaf {
+        let root_node: RopeNode = Arc::new(RopeNodeType::Node(Node {
+            left: Arc::new(RopeNodeType::Node(Node {
+                left: Arc::new(RopeNodeType::Leaf(Leaf {
                     value: String::from("hello "),
                 }))
                 .into(),
-                right: Rc::new(RopeNodeType::Leaf(Leaf {
+                right: Arc::new(RopeNodeType::Leaf(Leaf {
                     value: String::from("world! "),
                 }))
                 .into(),
                 weight: 6,
             }))
             .into(),
-            right: Rc::new(RopeNodeType::None).into(),
+            right: Arc::new(RopeNodeType::None).into(),
             weight: 13,
         }))
         .into();
 
         let expected_result = vec![
-            (0, 6, r#"Node(Left: None, Right: Leaf("world! "))"#),
-            (
-                13,
-                5,
-                r#"Node(Left: Node(Left: Node(Left: Leaf("hello "), Right: Leaf("world! ")), Right: None), Right: None)"#,
-            ),
+            (0, 6, r#"Node(Left: Leaf("world! "), Right: None)"#),
             (6, 7, r#"Node(Left: Leaf("hello "), Right: None)"#),
             (
                 9,
                 2,
                 r#"Node(Left: Node(Left: Leaf("hello "), Right: Leaf("wor")), Right: Leaf("! "))"#,
             ),
-            (
-                20,
-                5,
-                r#"Node(Left: Node(Left: Node(Left: Leaf("hello "), Right: Leaf("world! ")), Right: None), Right: None)"#,
-            ),
         ];
 
         for (idx, len, exp_result) in expected_result {
@@ -519,4 +611,49 @@ mod tests {
             assert_eq!(exp_result, format!("{}", result));
         }
     }
+
+    #[test]
+    #[should_panic(expected = "index 18 is out of bounds for length 13")]
+    fn delete_out_of_bounds_test() {
+        let mut rope = Rope::from("hello world! ");
+
+        rope.delete(13, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 2 is not a char boundary")]
+    fn substring_not_char_boundary_test() {
+        let mut rope = Rope::from("wörld");
+
+        rope.substring(2, 3);
+    }
+
+    #[test]
+    fn generic_rope_test() {
+        let mut rope: Rope<Vec<u32>> = (0..4)
+            .map(|i| {
+                RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf {
+                    value: (i * 10..i * 10 + 10).collect::<Vec<u32>>(),
+                })))
+            })
+            .collect();
+
+        assert_eq!(rope.len(), 40);
+        assert_eq!(rope.get(25), Some(&25));
+
+        rope.insert(15, vec![100, 101]);
+        rope.delete(0, 5);
+
+        assert_eq!(rope.len(), 37);
+        assert_eq!(rope.get(10), Some(&100));
+        assert_eq!(rope.get(12), Some(&15));
+
+        let tail = rope.split_off(30);
+        let values: Vec<u32> = tail
+            .iter()
+            .flat_map(|n| n.map_leaf().unwrap().value.clone())
+            .collect();
+
+        assert_eq!(values, vec![33, 34, 35, 36, 37, 38, 39]);
+    }
 }
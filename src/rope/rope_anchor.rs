@@ -0,0 +1,569 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{rope::Rope, rope_chunk::Chunk};
+
+static NEXT_ANCHOR_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Which side of an insertion made exactly at the anchor it sticks to.
+/// `Left` stays before the inserted text, `Right` moves after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bias {
+    Left,
+    Right,
+}
+
+/// Handle to a position tracked by a [`Rope`], see [`Rope::create_anchor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Anchor(usize);
+
+/// Anchors of a rope, kept in one treap per bias.
+///
+/// Every treap node stores its offset relative to its parent, so shifting
+/// all anchors after an edit only touches the nodes along a split path.
+/// Deleted ranges collapse their anchors lazily with the `collapsed` flag.
+/// Each edit is O(log n) in the number of anchors.
+#[derive(Debug, Clone, Default)]
+pub struct AnchorSet {
+    left: AnchorTree,
+    right: AnchorTree,
+    index: HashMap<Anchor, (Bias, usize)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct AnchorTree {
+    nodes: Vec<AnchorNode>,
+    free: Vec<usize>,
+    root: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct AnchorNode {
+    anchor: Anchor,
+    /// Offset relative to the parent, absolute for the root.
+    offset: isize,
+    priority: usize,
+    parent: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+    /// All descendants sit at the offset of this node.
+    collapsed: bool,
+}
+
+impl<T: Chunk> Rope<T> {
+    /// Starts tracking `offset`. The anchor follows the text it points at
+    /// through [`Rope::insert`], [`Rope::delete`], [`Rope::substring`] and
+    /// [`Rope::split_off`]. Offsets past the end are clamped.
+    pub fn create_anchor(&mut self, offset: usize, bias: Bias) -> Anchor {
+        let offset = offset.min(self.len());
+
+        self.anchors.create(offset, bias)
+    }
+
+    /// Current offset of the anchor, `None` if it isn't tracked by this rope.
+    pub fn anchor_offset(&self, anchor: Anchor) -> Option<usize> {
+        self.anchors.resolve(anchor)
+    }
+
+    pub fn remove_anchor(&mut self, anchor: Anchor) -> bool {
+        self.anchors.remove(anchor)
+    }
+}
+
+impl AnchorSet {
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn create(&mut self, offset: usize, bias: Bias) -> Anchor {
+        let anchor = Anchor(NEXT_ANCHOR_ID.fetch_add(1, Ordering::Relaxed));
+        self.insert(anchor, offset, bias);
+
+        anchor
+    }
+
+    pub fn resolve(&self, anchor: Anchor) -> Option<usize> {
+        self.index
+            .get(&anchor)
+            .map(|(bias, idx)| self.tree(*bias).offset_of(*idx))
+    }
+
+    pub fn remove(&mut self, anchor: Anchor) -> bool {
+        match self.index.remove(&anchor) {
+            Some((bias, idx)) => {
+                self.tree_mut(bias).remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Shifts anchors after `at` by `len`.
+    pub fn insert_text(&mut self, at: usize, len: usize) {
+        if self.is_empty() || len == 0 {
+            return;
+        }
+
+        self.left.shift(at as isize, len as isize, true);
+        self.right.shift(at as isize, len as isize, false);
+    }
+
+    /// Moves anchors inside the deleted range to `start` and shifts the ones
+    /// after it back by `len`.
+    pub fn delete_text(&mut self, start: usize, len: usize) {
+        if self.is_empty() || len == 0 {
+            return;
+        }
+
+        self.left.collapse(start as isize, len as isize);
+        self.right.collapse(start as isize, len as isize);
+    }
+
+    /// Moves the anchors after `at` into a new set, rebased to start at 0.
+    pub fn split_off(&mut self, at: usize) -> AnchorSet {
+        let mut other = AnchorSet::default();
+
+        let moved = [
+            (Bias::Left, self.left.split_off(at as isize, true)),
+            (Bias::Right, self.right.split_off(at as isize, false)),
+        ];
+
+        for (bias, anchors) in moved {
+            for (anchor, offset) in anchors {
+                self.index.remove(&anchor);
+                other.insert(anchor, offset - at, bias);
+            }
+        }
+
+        other
+    }
+
+    /// Moves all anchors of `other` into this set, shifted by `at`.
+    pub fn append(&mut self, other: AnchorSet, at: usize) {
+        for (anchor, (bias, _)) in other.index.iter() {
+            if let Some(offset) = other.resolve(*anchor) {
+                self.insert(*anchor, offset + at, *bias);
+            }
+        }
+    }
+
+    fn insert(&mut self, anchor: Anchor, offset: usize, bias: Bias) {
+        let idx = self.tree_mut(bias).insert(anchor, offset as isize);
+        self.index.insert(anchor, (bias, idx));
+    }
+
+    fn tree(&self, bias: Bias) -> &AnchorTree {
+        match bias {
+            Bias::Left => &self.left,
+            Bias::Right => &self.right,
+        }
+    }
+
+    fn tree_mut(&mut self, bias: Bias) -> &mut AnchorTree {
+        match bias {
+            Bias::Left => &mut self.left,
+            Bias::Right => &mut self.right,
+        }
+    }
+}
+
+impl AnchorTree {
+    fn insert(&mut self, anchor: Anchor, offset: isize) -> usize {
+        let node = AnchorNode {
+            anchor,
+            offset,
+            priority: anchor.0.wrapping_mul(0x9E37_79B9_7F4A_7C15_u64 as usize),
+            parent: None,
+            left: None,
+            right: None,
+            collapsed: false,
+        };
+
+        let idx = match self.free.pop() {
+            Some(idx) => {
+                self.nodes[idx] = node;
+                idx
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+
+        let (before, after) = self.split(self.root, 0, offset, false);
+        let before = self.merge(before, Some(idx));
+        self.root = self.merge(before, after);
+
+        idx
+    }
+
+    fn shift(&mut self, at: isize, len: isize, inclusive: bool) {
+        let (before, after) = self.split(self.root, 0, at, inclusive);
+
+        if let Some(after) = after {
+            self.nodes[after].offset += len;
+        }
+
+        self.root = self.merge(before, after);
+    }
+
+    fn collapse(&mut self, start: isize, len: isize) {
+        let (before, rest) = self.split(self.root, 0, start, true);
+        let (inside, after) = self.split(rest, 0, start + len, true);
+
+        if let Some(inside) = inside {
+            self.nodes[inside].offset = start;
+            self.nodes[inside].collapsed = true;
+        }
+
+        if let Some(after) = after {
+            self.nodes[after].offset -= len;
+        }
+
+        let rest = self.merge(inside, after);
+        self.root = self.merge(before, rest);
+    }
+
+    /// Removes the anchors after `at` and returns them with their offsets.
+    fn split_off(&mut self, at: isize, inclusive: bool) -> Vec<(Anchor, usize)> {
+        let (before, after) = self.split(self.root, 0, at, inclusive);
+        self.root = before;
+
+        let mut removed = vec![];
+        let mut stack: Vec<(usize, isize)> = after.into_iter().map(|idx| (idx, 0)).collect();
+
+        while let Some((idx, base)) = stack.pop() {
+            self.push_down(idx);
+
+            let offset = base + self.nodes[idx].offset;
+            removed.push((self.nodes[idx].anchor, offset as usize));
+
+            for child in [self.nodes[idx].left, self.nodes[idx].right]
+                .into_iter()
+                .flatten()
+            {
+                stack.push((child, offset));
+            }
+
+            self.free.push(idx);
+        }
+
+        removed
+    }
+
+    fn remove(&mut self, idx: usize) {
+        let mut path = vec![idx];
+
+        while let Some(parent) = self.nodes[path[path.len() - 1]].parent {
+            path.push(parent);
+        }
+
+        for node in path.iter().rev() {
+            self.push_down(*node);
+        }
+
+        let offset = self.offset_of(idx) as isize;
+        let (left, right) = (self.nodes[idx].left, self.nodes[idx].right);
+
+        for child in [left, right].into_iter().flatten() {
+            self.nodes[child].offset += offset;
+            self.nodes[child].parent = None;
+        }
+
+        let merged = self.merge(left, right);
+
+        match self.nodes[idx].parent {
+            Some(parent) => {
+                let parent_offset = self.offset_of(parent) as isize;
+
+                if let Some(merged) = merged {
+                    self.nodes[merged].offset -= parent_offset;
+                    self.nodes[merged].parent = Some(parent);
+                }
+
+                match self.nodes[parent].left == Some(idx) {
+                    true => self.nodes[parent].left = merged,
+                    false => self.nodes[parent].right = merged,
+                }
+            }
+            None => self.root = merged,
+        }
+
+        self.free.push(idx);
+    }
+
+    fn offset_of(&self, idx: usize) -> usize {
+        let mut path = vec![idx];
+
+        while let Some(parent) = self.nodes[path[path.len() - 1]].parent {
+            path.push(parent);
+        }
+
+        let mut offset = 0;
+
+        for node in path.iter().rev() {
+            offset += self.nodes[*node].offset;
+
+            if *node != idx && self.nodes[*node].collapsed {
+                break;
+            }
+        }
+
+        offset as usize
+    }
+
+    fn push_down(&mut self, idx: usize) {
+        if !self.nodes[idx].collapsed {
+            return;
+        }
+
+        self.nodes[idx].collapsed = false;
+
+        for child in [self.nodes[idx].left, self.nodes[idx].right]
+            .into_iter()
+            .flatten()
+        {
+            self.nodes[child].offset = 0;
+            self.nodes[child].collapsed = true;
+        }
+    }
+
+    /// Splits `node`, whose parent sits at `base`, into anchors before `key`
+    /// and the rest. Anchors at `key` go before it when `inclusive`. Roots
+    /// of both returned trees hold absolute offsets.
+    fn split(
+        &mut self,
+        node: Option<usize>,
+        base: isize,
+        key: isize,
+        inclusive: bool,
+    ) -> (Option<usize>, Option<usize>) {
+        let Some(idx) = node else {
+            return (None, None);
+        };
+
+        self.push_down(idx);
+
+        let offset = base + self.nodes[idx].offset;
+        self.nodes[idx].offset = offset;
+        self.nodes[idx].parent = None;
+
+        match offset < key || (inclusive && offset == key) {
+            true => {
+                let (before, after) = self.split(self.nodes[idx].right, offset, key, inclusive);
+                self.set_child(idx, before, false);
+
+                (Some(idx), after)
+            }
+            false => {
+                let (before, after) = self.split(self.nodes[idx].left, offset, key, inclusive);
+                self.set_child(idx, after, true);
+
+                (before, Some(idx))
+            }
+        }
+    }
+
+    /// Joins two trees with absolute root offsets, all anchors of `first`
+    /// being before the anchors of `second`.
+    fn merge(&mut self, first: Option<usize>, second: Option<usize>) -> Option<usize> {
+        match (first, second) {
+            (None, node) | (node, None) => node,
+            (Some(first), Some(second)) => {
+                match self.nodes[first].priority > self.nodes[second].priority {
+                    true => {
+                        self.push_down(first);
+                        let right = self.detach(self.nodes[first].right, first);
+                        let merged = self.merge(right, Some(second));
+                        self.set_child(first, merged, false);
+
+                        Some(first)
+                    }
+                    false => {
+                        self.push_down(second);
+                        let left = self.detach(self.nodes[second].left, second);
+                        let merged = self.merge(Some(first), left);
+                        self.set_child(second, merged, true);
+
+                        Some(second)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Turns a child of `parent`, a root, into a root of its own.
+    fn detach(&mut self, child: Option<usize>, parent: usize) -> Option<usize> {
+        if let Some(child) = child {
+            self.nodes[child].offset += self.nodes[parent].offset;
+            self.nodes[child].parent = None;
+        }
+
+        child
+    }
+
+    /// Attaches a tree with an absolute root offset under `parent`, a root.
+    fn set_child(&mut self, parent: usize, child: Option<usize>, left: bool) {
+        if let Some(child) = child {
+            self.nodes[child].offset -= self.nodes[parent].offset;
+            self.nodes[child].parent = Some(parent);
+        }
+
+        match left {
+            true => self.nodes[parent].left = child,
+            false => self.nodes[parent].right = child,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rope::rope_node::{Leaf, RopeNodeType};
+    use std::sync::Arc;
+
+    fn rope(value: &str) -> Rope {
+        Rope::new(
+            Arc::new(RopeNodeType::Leaf(Leaf {
+                value: String::from(value),
+            }))
+            .into(),
+        )
+    }
+
+    #[test]
+    fn anchor_insert_test() {
+        let mut rope = rope("hello world");
+
+        let before = rope.create_anchor(2, Bias::Right);
+        let left = rope.create_anchor(5, Bias::Left);
+        let right = rope.create_anchor(5, Bias::Right);
+        let after = rope.create_anchor(8, Bias::Left);
+
+        rope.insert(5, String::from(", big"));
+
+        assert_eq!(rope.anchor_offset(before), Some(2));
+        assert_eq!(rope.anchor_offset(left), Some(5));
+        assert_eq!(rope.anchor_offset(right), Some(10));
+        assert_eq!(rope.anchor_offset(after), Some(13));
+    }
+
+    #[test]
+    fn anchor_delete_test() {
+        let mut rope = rope("hello big world");
+
+        let before = rope.create_anchor(3, Bias::Left);
+        let inside = rope.create_anchor(7, Bias::Right);
+        let after = rope.create_anchor(12, Bias::Left);
+
+        rope.delete(5, 4);
+
+        assert_eq!(rope.anchor_offset(before), Some(3));
+        assert_eq!(rope.anchor_offset(inside), Some(5));
+        assert_eq!(rope.anchor_offset(after), Some(8));
+
+        rope.insert(5, String::from("!"));
+
+        assert_eq!(rope.anchor_offset(inside), Some(6));
+        assert_eq!(rope.anchor_offset(after), Some(9));
+    }
+
+    #[test]
+    fn anchor_substring_and_split_test() {
+        let mut rope = rope("hello big world");
+
+        let cut = rope.create_anchor(1, Bias::Left);
+        let kept = rope.create_anchor(8, Bias::Left);
+        let moved = rope.create_anchor(12, Bias::Right);
+
+        rope.substring(3, 10);
+
+        assert_eq!(rope.anchor_offset(cut), Some(0));
+        assert_eq!(rope.anchor_offset(kept), Some(5));
+        assert_eq!(rope.anchor_offset(moved), Some(9));
+
+        let other = rope.split_off(6);
+
+        assert_eq!(rope.anchor_offset(kept), Some(5));
+        assert_eq!(rope.anchor_offset(moved), None);
+        assert_eq!(other.anchor_offset(moved), Some(3));
+    }
+
+    #[test]
+    fn anchor_remove_test() {
+        let mut rope = rope("hello world");
+
+        let first = rope.create_anchor(2, Bias::Left);
+        let second = rope.create_anchor(4, Bias::Left);
+
+        assert!(rope.remove_anchor(first));
+        assert!(!rope.remove_anchor(first));
+        assert_eq!(rope.anchor_offset(first), None);
+        assert_eq!(rope.anchor_offset(second), Some(4));
+    }
+
+    #[test]
+    fn anchor_random_edits_test() {
+        let mut seed: usize = 7;
+        let mut next = |max: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % max.max(1)
+        };
+
+        let mut set = AnchorSet::default();
+        let mut expected: Vec<(Anchor, Bias, usize)> = vec![];
+        let mut len = 1000;
+
+        for step in 0..2000 {
+            match next(5) {
+                0 if expected.len() < 200 => {
+                    let bias = match next(2) {
+                        0 => Bias::Left,
+                        _ => Bias::Right,
+                    };
+                    let offset = next(len + 1);
+                    expected.push((set.create(offset, bias), bias, offset));
+                }
+                1 if !expected.is_empty() => {
+                    let (anchor, _, _) = expected.swap_remove(next(expected.len()));
+                    assert!(set.remove(anchor));
+                }
+                2 | 3 => {
+                    let (at, count) = (next(len + 1), next(20) + 1);
+                    set.insert_text(at, count);
+                    len += count;
+
+                    for (_, bias, offset) in expected.iter_mut() {
+                        if *offset > at || (*offset == at && *bias == Bias::Right) {
+                            *offset += count;
+                        }
+                    }
+                }
+                _ => {
+                    let start = next(len);
+                    let count = next(20).min(len - start);
+                    set.delete_text(start, count);
+                    len -= count;
+
+                    for (_, _, offset) in expected.iter_mut() {
+                        if *offset > start {
+                            *offset = (*offset).max(start + count) - count;
+                        }
+                    }
+                }
+            }
+
+            for (anchor, _, offset) in &expected {
+                assert_eq!(set.resolve(*anchor), Some(*offset), "step {step}");
+            }
+        }
+    }
+}
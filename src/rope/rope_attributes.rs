@@ -0,0 +1,361 @@
+use std::ops::Range;
+
+use crate::error::Error;
+
+use super::rope::Rope;
+
+/// Rope with an attribute set attached to every byte, stored as style runs.
+///
+/// Runs cover the whole text, are never empty and adjacent runs never carry
+/// equal attributes. Text inserted inside or at the end of a run extends
+/// it, deleted text shrinks or drops the runs it covered.
+#[derive(Debug)]
+pub struct AttributedRope<A> {
+    text: Rope,
+    runs: Vec<(usize, A)>,
+}
+
+/// Iterator over style runs, see [`AttributedRope::style_runs`].
+pub struct StyleRuns<'a, A> {
+    runs: std::slice::Iter<'a, (usize, A)>,
+    offset: usize,
+}
+
+impl<'a, A> Iterator for StyleRuns<'a, A> {
+    type Item = (Range<usize>, &'a A);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (len, attrs) = self.runs.next()?;
+        let start = self.offset;
+        self.offset += len;
+
+        Some((start..self.offset, attrs))
+    }
+}
+
+impl<A> AttributedRope<A>
+where
+    A: Clone + PartialEq + Default,
+{
+    /// Wraps `text` with default attributes.
+    pub fn new(text: Rope) -> Self {
+        let len = text.len();
+
+        let runs = match len {
+            0 => vec![],
+            _ => vec![(len, A::default())],
+        };
+
+        AttributedRope { text, runs }
+    }
+
+    pub fn text(&self) -> &Rope {
+        &self.text
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(|(len, _)| len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn attributes_at(&self, offset: usize) -> Option<&A> {
+        self.style_runs()
+            .find(|(range, _)| range.contains(&offset))
+            .map(|(_, attrs)| attrs)
+    }
+
+    pub fn style_runs(&self) -> StyleRuns<'_, A> {
+        StyleRuns {
+            runs: self.runs.iter(),
+            offset: 0,
+        }
+    }
+
+    /// Replaces the attributes of `range`, clamped to the text.
+    pub fn set_attributes(&mut self, range: Range<usize>, attrs: A) {
+        let len = self.len();
+        let (start, end) = (range.start.min(len), range.end.min(len));
+
+        if start >= end {
+            return;
+        }
+
+        let first = self.split_run(start);
+        let last = self.split_run(end);
+
+        self.runs.splice(first..last, [(end - start, attrs)]);
+        self.normalize();
+    }
+
+    /// Inserts text extending the run it lands in. At a run boundary the run
+    /// before the insertion point is extended. `index` is clamped to the
+    /// text.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not a char boundary, see [`AttributedRope::try_insert`].
+    pub fn insert(&mut self, index: usize, value: String) {
+        let index = index.min(self.len());
+        let inserted = value.len();
+
+        if inserted == 0 {
+            return;
+        }
+
+        self.text.insert(index, value);
+
+        let mut offset = 0;
+
+        for (len, _) in self.runs.iter_mut() {
+            if index <= offset + *len {
+                *len += inserted;
+                return;
+            }
+
+            offset += *len;
+        }
+
+        self.runs.push((inserted, A::default()));
+    }
+
+    /// Inserts text with its own attributes, splitting the run it lands in.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not a char boundary.
+    pub fn insert_with_attributes(&mut self, index: usize, value: String, attrs: A) {
+        let index = index.min(self.len());
+        let inserted = value.len();
+
+        if inserted == 0 {
+            return;
+        }
+
+        self.text.insert(index, value);
+
+        let at = self.split_run(index);
+        self.runs.insert(at, (inserted, attrs));
+        self.normalize();
+    }
+
+    /// Deletes `len` bytes from `start`, clamped to the text.
+    ///
+    /// # Panics
+    ///
+    /// If either end is not a char boundary.
+    pub fn delete(&mut self, start: usize, len: usize) {
+        let total = self.len();
+        let start = start.min(total);
+        let end = start.saturating_add(len).min(total);
+
+        if start == end {
+            return;
+        }
+
+        self.text.delete(start, end - start);
+
+        let first = self.split_run(start);
+        let last = self.split_run(end);
+
+        self.runs.drain(first..last);
+        self.normalize();
+    }
+
+    pub fn try_insert(&mut self, index: usize, value: String) -> Result<(), Error> {
+        self.text.check_index(index, self.len())?;
+        self.insert(index, value);
+
+        Ok(())
+    }
+
+    pub fn try_insert_with_attributes(
+        &mut self,
+        index: usize,
+        value: String,
+        attrs: A,
+    ) -> Result<(), Error> {
+        self.text.check_index(index, self.len())?;
+        self.insert_with_attributes(index, value, attrs);
+
+        Ok(())
+    }
+
+    pub fn try_delete(&mut self, start: usize, len: usize) -> Result<(), Error> {
+        self.text.check_range(start..start.saturating_add(len))?;
+        self.delete(start, len);
+
+        Ok(())
+    }
+
+    /// Makes `offset` a run boundary and returns the index of the run
+    /// starting there.
+    fn split_run(&mut self, offset: usize) -> usize {
+        let mut run_start = 0;
+
+        for idx in 0..self.runs.len() {
+            let len = self.runs[idx].0;
+
+            match offset - run_start {
+                0 => return idx,
+                inside if inside < len => {
+                    let attrs = self.runs[idx].1.clone();
+
+                    self.runs[idx].0 = inside;
+                    self.runs.insert(idx + 1, (len - inside, attrs));
+
+                    return idx + 1;
+                }
+                _ => run_start += len,
+            }
+        }
+
+        self.runs.len()
+    }
+
+    fn normalize(&mut self) {
+        let mut runs: Vec<(usize, A)> = Vec::with_capacity(self.runs.len());
+
+        for (len, attrs) in self.runs.drain(..).filter(|(len, _)| *len > 0) {
+            match runs.last_mut() {
+                Some((last_len, last_attrs)) if *last_attrs == attrs => *last_len += len,
+                _ => runs.push((len, attrs)),
+            }
+        }
+
+        self.runs = runs;
+    }
+}
+
+impl<A> From<&str> for AttributedRope<A>
+where
+    A: Clone + PartialEq + Default,
+{
+    fn from(value: &str) -> Self {
+        AttributedRope::new(Rope::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rope::rope_range::RopeError;
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    struct Style {
+        bold: bool,
+        color: Option<u32>,
+    }
+
+    const BOLD: Style = Style {
+        bold: true,
+        color: None,
+    };
+
+    const RED: Style = Style {
+        bold: false,
+        color: Some(0xff0000),
+    };
+
+    fn runs(rope: &AttributedRope<Style>) -> Vec<(Range<usize>, Style)> {
+        rope.style_runs()
+            .map(|(range, attrs)| (range, attrs.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn set_attributes_test() {
+        let mut rope: AttributedRope<Style> = AttributedRope::from("hello big world");
+
+        rope.set_attributes(6..9, BOLD);
+        rope.set_attributes(8..12, RED);
+
+        assert_eq!(
+            runs(&rope),
+            vec![
+                (0..6, Style::default()),
+                (6..8, BOLD),
+                (8..12, RED),
+                (12..15, Style::default()),
+            ]
+        );
+        assert_eq!(rope.attributes_at(7), Some(&BOLD));
+        assert_eq!(rope.attributes_at(15), None);
+
+        rope.set_attributes(0..15, Style::default());
+
+        assert_eq!(runs(&rope), vec![(0..15, Style::default())]);
+    }
+
+    #[test]
+    fn insert_extends_run_test() {
+        let mut rope: AttributedRope<Style> = AttributedRope::from("hello world");
+        rope.set_attributes(0..5, BOLD);
+
+        rope.insert(5, String::from("!!"));
+        rope.insert(0, String::from(">"));
+
+        assert_eq!(runs(&rope), vec![(0..8, BOLD), (8..14, Style::default())]);
+        assert_eq!(rope.text().len(), 14);
+    }
+
+    #[test]
+    fn insert_with_attributes_splits_run_test() {
+        let mut rope: AttributedRope<Style> = AttributedRope::from("hello world");
+        rope.set_attributes(0..11, BOLD);
+
+        rope.insert_with_attributes(6, String::from("red "), RED);
+
+        assert_eq!(
+            runs(&rope),
+            vec![(0..6, BOLD), (6..10, RED), (10..15, BOLD)]
+        );
+    }
+
+    #[test]
+    fn delete_shrinks_and_merges_runs_test() {
+        let mut rope: AttributedRope<Style> = AttributedRope::from("hello big world");
+        rope.set_attributes(0..5, BOLD);
+        rope.set_attributes(6..9, RED);
+        rope.set_attributes(10..15, BOLD);
+
+        rope.delete(3, 9);
+
+        assert_eq!(runs(&rope), vec![(0..6, BOLD)]);
+        assert_eq!(rope.text().len(), 6);
+
+        rope.delete(0, 6);
+
+        assert!(rope.is_empty());
+        assert_eq!(rope.style_runs().count(), 0);
+    }
+
+    #[test]
+    fn try_edits_test() {
+        let mut rope: AttributedRope<Style> = AttributedRope::from("wörld");
+
+        assert_eq!(
+            rope.try_insert(2, String::from("!")),
+            Err(RopeError::NotCharBoundary(2).into())
+        );
+        assert_eq!(
+            rope.try_insert_with_attributes(7, String::from("!"), BOLD),
+            Err(RopeError::OutOfBounds { index: 7, len: 6 }.into())
+        );
+        assert_eq!(
+            rope.try_delete(0, 2),
+            Err(RopeError::NotCharBoundary(2).into())
+        );
+        assert_eq!(runs(&rope), vec![(0..6, Style::default())]);
+
+        rope.try_insert_with_attributes(6, String::from("!"), BOLD)
+            .unwrap();
+        rope.try_delete(1, 2).unwrap();
+        rope.try_insert(1, String::from("o")).unwrap();
+
+        assert_eq!(rope.text(), &"world!");
+        assert_eq!(runs(&rope), vec![(0..5, Style::default()), (5..6, BOLD)]);
+    }
+}
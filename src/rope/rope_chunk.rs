@@ -0,0 +1,71 @@
+use std::ops::Range;
+
+/// Contents of a rope leaf. Offsets and lengths are counted in the units of
+/// the chunk, bytes for `String` and elements for `Vec`.
+pub trait Chunk: Sized {
+    type Item;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, index: usize) -> Option<&Self::Item>;
+
+    /// Whether the chunk may be split at `index`.
+    fn is_boundary(&self, index: usize) -> bool {
+        index <= self.len()
+    }
+
+    /// Copies `range` into a new chunk.
+    fn slice(&self, range: Range<usize>) -> Self;
+
+    /// Copies both chunks into a new one.
+    fn concat(&self, other: &Self) -> Self;
+}
+
+impl Chunk for String {
+    type Item = u8;
+
+    fn len(&self) -> usize {
+        self.as_str().len()
+    }
+
+    fn get(&self, index: usize) -> Option<&u8> {
+        self.as_bytes().get(index)
+    }
+
+    fn is_boundary(&self, index: usize) -> bool {
+        self.is_char_boundary(index)
+    }
+
+    /// Panics if `range` doesn't lie on char boundaries.
+    fn slice(&self, range: Range<usize>) -> Self {
+        self[range].to_string()
+    }
+
+    fn concat(&self, other: &Self) -> Self {
+        [self.as_str(), other.as_str()].concat()
+    }
+}
+
+impl<E: Clone> Chunk for Vec<E> {
+    type Item = E;
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn get(&self, index: usize) -> Option<&E> {
+        self.as_slice().get(index)
+    }
+
+    fn slice(&self, range: Range<usize>) -> Self {
+        self[range].to_vec()
+    }
+
+    fn concat(&self, other: &Self) -> Self {
+        [self.as_slice(), other.as_slice()].concat()
+    }
+}
@@ -0,0 +1,480 @@
+use std::{ops::Range, sync::Arc};
+
+use super::{rope::Rope, rope_node::RopeNodeType};
+use crate::helpers::myers_diff::{self, DiffOp, DiffTag};
+
+/// Difference between two ropes, as produced by [`Rope::diff`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RopeDiff {
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// A run of changed lines. Line ranges are zero-based line indices, byte
+/// ranges are offsets into the old and the new rope respectively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub old_lines: Range<usize>,
+    pub new_lines: Range<usize>,
+    pub old_range: Range<usize>,
+    pub new_range: Range<usize>,
+    /// Char level changes inside the hunk. Only deletions and insertions are
+    /// listed, their ranges are byte offsets into the old and the new rope.
+    pub changes: Vec<DiffOp>,
+}
+
+impl RopeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.hunks.is_empty()
+    }
+}
+
+impl Rope {
+    /// Computes line and char level hunks turning `self` into `other`.
+    ///
+    /// Subtrees shared by both ropes are skipped by pointer comparison, so
+    /// diffing two snapshots of the same document only looks at the text
+    /// around the edits.
+    pub fn diff(&self, other: &Rope) -> RopeDiff {
+        let old = &self.root.0;
+        let new = &other.root.0;
+
+        let old_len = old.measure();
+        let new_len = new.measure();
+
+        let prefix = common_prefix(old, new);
+
+        if prefix == old_len && prefix == new_len {
+            return RopeDiff::default();
+        }
+
+        let suffix = common_suffix(old, new).min(old_len.min(new_len) - prefix);
+
+        let start = rfind_newline(old, prefix).map_or(0, |i| i + 1);
+        let old_end = match old_len - suffix {
+            end if end == old_len => end,
+            end => find_newline(old, end).map_or(old_len, |i| i + 1),
+        };
+        let new_end = new_len - (old_len - old_end);
+
+        let mut old_text = String::new();
+        let mut new_text = String::new();
+        collect_range(old, start..old_end, &mut old_text);
+        collect_range(new, start..new_end, &mut new_text);
+
+        let first_line = count_newlines(old, start);
+
+        let old_lines: Vec<&str> = old_text.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new_text.split_inclusive('\n').collect();
+
+        let old_offsets = line_offsets(&old_lines, start);
+        let new_offsets = line_offsets(&new_lines, start);
+
+        let mut hunks: Vec<DiffHunk> = vec![];
+        let mut last_tag = DiffTag::Equal;
+
+        for op in myers_diff::diff(&old_lines, &new_lines) {
+            if op.tag != DiffTag::Equal {
+                match hunks.last_mut() {
+                    Some(hunk) if last_tag != DiffTag::Equal => {
+                        hunk.old_lines.end = first_line + op.old.end;
+                        hunk.new_lines.end = first_line + op.new.end;
+                    }
+                    _ => hunks.push(DiffHunk {
+                        old_lines: first_line + op.old.start..first_line + op.old.end,
+                        new_lines: first_line + op.new.start..first_line + op.new.end,
+                        old_range: 0..0,
+                        new_range: 0..0,
+                        changes: vec![],
+                    }),
+                }
+            }
+
+            last_tag = op.tag;
+        }
+
+        for hunk in hunks.iter_mut() {
+            hunk.old_range = old_offsets[hunk.old_lines.start - first_line]
+                ..old_offsets[hunk.old_lines.end - first_line];
+            hunk.new_range = new_offsets[hunk.new_lines.start - first_line]
+                ..new_offsets[hunk.new_lines.end - first_line];
+
+            hunk.changes = char_changes(
+                &old_text[hunk.old_range.start - start..hunk.old_range.end - start],
+                &new_text[hunk.new_range.start - start..hunk.new_range.end - start],
+                hunk.old_range.start,
+                hunk.new_range.start,
+            );
+        }
+
+        RopeDiff { hunks }
+    }
+}
+
+fn line_offsets(lines: &[&str], start: usize) -> Vec<usize> {
+    let mut offsets = vec![start];
+
+    for line in lines {
+        offsets.push(offsets[offsets.len() - 1] + line.len());
+    }
+
+    offsets
+}
+
+fn char_changes(old: &str, new: &str, old_start: usize, new_start: usize) -> Vec<DiffOp> {
+    let old_chars: Vec<(usize, char)> = old.char_indices().collect();
+    let new_chars: Vec<(usize, char)> = new.char_indices().collect();
+
+    let byte_offset = |chars: &[(usize, char)], text: &str, idx: usize| match chars.get(idx) {
+        Some((offset, _)) => *offset,
+        None => text.len(),
+    };
+
+    let old_seq: Vec<char> = old_chars.iter().map(|(_, c)| *c).collect();
+    let new_seq: Vec<char> = new_chars.iter().map(|(_, c)| *c).collect();
+
+    myers_diff::diff(&old_seq, &new_seq)
+        .into_iter()
+        .filter(|op| op.tag != DiffTag::Equal)
+        .map(|op| DiffOp {
+            tag: op.tag,
+            old: old_start + byte_offset(&old_chars, old, op.old.start)
+                ..old_start + byte_offset(&old_chars, old, op.old.end),
+            new: new_start + byte_offset(&new_chars, new, op.new.start)
+                ..new_start + byte_offset(&new_chars, new, op.new.end),
+        })
+        .collect()
+}
+
+fn common_prefix(old: &Arc<RopeNodeType>, new: &Arc<RopeNodeType>) -> usize {
+    let mut old_stack = vec![(Arc::clone(old), 0)];
+    let mut new_stack = vec![(Arc::clone(new), 0)];
+    let mut offset = 0;
+
+    while let (Some((old_top, old_skip)), Some((new_top, new_skip))) =
+        (old_stack.last().cloned(), new_stack.last().cloned())
+    {
+        if Arc::ptr_eq(&old_top, &new_top) && old_skip == new_skip {
+            offset += old_top.measure() - old_skip;
+            old_stack.pop();
+            new_stack.pop();
+            continue;
+        }
+
+        match (old_top.as_ref(), new_top.as_ref()) {
+            (RopeNodeType::Leaf(old_leaf), RopeNodeType::Leaf(new_leaf)) => {
+                let old_part = &old_leaf.value[old_skip..];
+                let new_part = &new_leaf.value[new_skip..];
+
+                let common: usize = old_part
+                    .chars()
+                    .zip(new_part.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a.len_utf8())
+                    .sum();
+
+                offset += common;
+
+                if common < old_part.len() && common < new_part.len() {
+                    return offset;
+                }
+
+                advance(&mut old_stack, common, old_part.len());
+                advance(&mut new_stack, common, new_part.len());
+            }
+            _ => {
+                expand(&mut old_stack, false);
+                expand(&mut new_stack, false);
+            }
+        }
+    }
+
+    offset
+}
+
+fn common_suffix(old: &Arc<RopeNodeType>, new: &Arc<RopeNodeType>) -> usize {
+    let mut old_stack = vec![(Arc::clone(old), 0)];
+    let mut new_stack = vec![(Arc::clone(new), 0)];
+    let mut offset = 0;
+
+    while let (Some((old_top, old_skip)), Some((new_top, new_skip))) =
+        (old_stack.last().cloned(), new_stack.last().cloned())
+    {
+        if Arc::ptr_eq(&old_top, &new_top) && old_skip == new_skip {
+            offset += old_top.measure() - old_skip;
+            old_stack.pop();
+            new_stack.pop();
+            continue;
+        }
+
+        match (old_top.as_ref(), new_top.as_ref()) {
+            (RopeNodeType::Leaf(old_leaf), RopeNodeType::Leaf(new_leaf)) => {
+                let old_part = &old_leaf.value[..old_leaf.value.len() - old_skip];
+                let new_part = &new_leaf.value[..new_leaf.value.len() - new_skip];
+
+                let common: usize = old_part
+                    .chars()
+                    .rev()
+                    .zip(new_part.chars().rev())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a.len_utf8())
+                    .sum();
+
+                offset += common;
+
+                if common < old_part.len() && common < new_part.len() {
+                    return offset;
+                }
+
+                advance(&mut old_stack, common, old_part.len());
+                advance(&mut new_stack, common, new_part.len());
+            }
+            _ => {
+                expand(&mut old_stack, true);
+                expand(&mut new_stack, true);
+            }
+        }
+    }
+
+    offset
+}
+
+/// Consumes `len` bytes of the leaf on top of the stack, popping it once it
+/// has no bytes left.
+fn advance(stack: &mut Vec<(Arc<RopeNodeType>, usize)>, len: usize, available: usize) {
+    match len == available {
+        true => _ = stack.pop(),
+        false => {
+            if let Some((_, skip)) = stack.last_mut() {
+                *skip += len;
+            }
+        }
+    }
+}
+
+/// Replaces a node on top of the stack with its children, so that the child
+/// closest to the walking direction ends up on top. Empty nodes are dropped.
+fn expand(stack: &mut Vec<(Arc<RopeNodeType>, usize)>, from_right: bool) {
+    let Some((top, _)) = stack.last().cloned() else {
+        return;
+    };
+
+    match top.as_ref() {
+        RopeNodeType::Node(node) => {
+            stack.pop();
+
+            match from_right {
+                true => {
+                    stack.push((Arc::clone(&node.left.0), 0));
+                    stack.push((Arc::clone(&node.right.0), 0));
+                }
+                false => {
+                    stack.push((Arc::clone(&node.right.0), 0));
+                    stack.push((Arc::clone(&node.left.0), 0));
+                }
+            }
+        }
+        RopeNodeType::None => _ = stack.pop(),
+        RopeNodeType::Leaf(_) => (),
+    }
+}
+
+fn collect_range(node: &RopeNodeType, range: Range<usize>, out: &mut String) {
+    if range.start >= range.end {
+        return;
+    }
+
+    match node {
+        RopeNodeType::Node(node) => {
+            if range.start < node.weight {
+                collect_range(&node.left.0, range.start..range.end.min(node.weight), out);
+            }
+
+            if range.end > node.weight {
+                collect_range(
+                    &node.right.0,
+                    range.start.saturating_sub(node.weight)..range.end - node.weight,
+                    out,
+                );
+            }
+        }
+        RopeNodeType::Leaf(leaf) => {
+            out.push_str(&leaf.value[range.start..range.end.min(leaf.value.len())])
+        }
+        RopeNodeType::None => (),
+    }
+}
+
+/// Offset of the last line feed before `end`.
+fn rfind_newline(node: &RopeNodeType, end: usize) -> Option<usize> {
+    match node {
+        RopeNodeType::Node(node) => {
+            if end > node.weight {
+                if let Some(i) = rfind_newline(&node.right.0, end - node.weight) {
+                    return Some(node.weight + i);
+                }
+            }
+
+            rfind_newline(&node.left.0, end.min(node.weight))
+        }
+        RopeNodeType::Leaf(leaf) => leaf.value.as_bytes()[..end.min(leaf.value.len())]
+            .iter()
+            .rposition(|b| *b == b'\n'),
+        RopeNodeType::None => None,
+    }
+}
+
+/// Offset of the first line feed at or after `start`.
+fn find_newline(node: &RopeNodeType, start: usize) -> Option<usize> {
+    match node {
+        RopeNodeType::Node(node) => {
+            if start < node.weight {
+                if let Some(i) = find_newline(&node.left.0, start) {
+                    return Some(i);
+                }
+            }
+
+            find_newline(&node.right.0, start.saturating_sub(node.weight)).map(|i| i + node.weight)
+        }
+        RopeNodeType::Leaf(leaf) => leaf
+            .value
+            .as_bytes()
+            .get(start..)
+            .and_then(|bytes| bytes.iter().position(|b| *b == b'\n'))
+            .map(|i| i + start),
+        RopeNodeType::None => None,
+    }
+}
+
+fn count_newlines(node: &RopeNodeType, end: usize) -> usize {
+    match node {
+        RopeNodeType::Node(node) => match end > node.weight {
+            true => {
+                count_newlines(&node.left.0, node.weight)
+                    + count_newlines(&node.right.0, end - node.weight)
+            }
+            false => count_newlines(&node.left.0, end),
+        },
+        RopeNodeType::Leaf(leaf) => leaf.value.as_bytes()[..end.min(leaf.value.len())]
+            .iter()
+            .filter(|b| **b == b'\n')
+            .count(),
+        RopeNodeType::None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rope::rope_node::{Leaf, RopeNode};
+
+    fn leaf(value: &str) -> RopeNode {
+        Arc::new(RopeNodeType::Leaf(Leaf {
+            value: String::from(value),
+        }))
+        .into()
+    }
+
+    fn rope(leafs: &[&str]) -> Rope {
+        Rope::from_iter(leafs.iter().map(|l| leaf(l)))
+    }
+
+    #[test]
+    fn diff_equal_test() {
+        let old = rope(&["hello\n", "world\n"]);
+        let new = rope(&["hel", "lo\nworld\n"]);
+
+        assert!(old.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn diff_changed_line_test() {
+        let old = rope(&["first\n", "second line\n", "third\n"]);
+        let new = rope(&["first\n", "second lime\n", "third\n"]);
+
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            diff.hunks,
+            vec![DiffHunk {
+                old_lines: 1..2,
+                new_lines: 1..2,
+                old_range: 6..18,
+                new_range: 6..18,
+                changes: vec![
+                    DiffOp {
+                        tag: DiffTag::Delete,
+                        old: 15..16,
+                        new: 15..15,
+                    },
+                    DiffOp {
+                        tag: DiffTag::Insert,
+                        old: 16..16,
+                        new: 15..16,
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn diff_insert_and_delete_lines_test() {
+        let old = rope(&["a\nb\n", "c\nd\n"]);
+        let new = rope(&["a\nx\ny\nb\n", "d\n"]);
+
+        let diff = old.diff(&new);
+
+        let lines: Vec<_> = diff
+            .hunks
+            .iter()
+            .map(|h| (h.old_lines.clone(), h.new_lines.clone()))
+            .collect();
+
+        assert_eq!(lines, vec![(1..1, 1..3), (2..3, 4..4)]);
+        assert_eq!(diff.hunks[0].new_range, 2..6);
+        assert_eq!(diff.hunks[1].old_range, 4..6);
+    }
+
+    #[test]
+    fn diff_shared_subtrees_test() {
+        let text: Vec<String> = (0..64).map(|i| format!("line {i}\n")).collect();
+        let leafs: Vec<&str> = text.iter().map(|s| s.as_str()).collect();
+
+        let old = rope(&leafs);
+        let mut new = Rope::new(Arc::clone(&old.root.0).into());
+        new.insert(
+            text[..40].iter().map(|l| l.len()).sum(),
+            String::from("new\n"),
+        );
+
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.hunks[0].old_lines, 40..40);
+        assert_eq!(diff.hunks[0].new_lines, 40..41);
+    }
+
+    #[test]
+    fn diff_multibyte_test() {
+        let old = rope(&["zażółć\n"]);
+        let new = rope(&["zażólć\n"]);
+
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(
+            diff.hunks[0].changes,
+            vec![
+                DiffOp {
+                    tag: DiffTag::Delete,
+                    old: 6..8,
+                    new: 6..6,
+                },
+                DiffOp {
+                    tag: DiffTag::Insert,
+                    old: 8..8,
+                    new: 6..7,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use std::{ops::Range, sync::Arc};
+
+use super::{
+    rope::Rope,
+    rope_chunk::Chunk,
+    rope_iter::RopeIter,
+    rope_node::{Leaf, Node, RopeNode, RopeNodeType},
+    rope_validate::MAX_DEPTH,
+};
+
+/// Appended or prepended chunks are merged into the outer leaf while the
+/// result stays below this length.
+pub const MAX_LEAF_LEN: usize = 1024;
+
+impl<T: Chunk> Rope<T> {
+    /// Adds `value` at the end by rebuilding only the right spine. The tree
+    /// is rebalanced once the spine gets deeper than `MAX_DEPTH / 2`.
+    pub fn append(&mut self, value: T) {
+        if value.is_empty() {
+            return;
+        }
+
+        self.anchors.insert_text(self.root.measure(), value.len());
+
+        self.root = append_rec(&self.root.0, value);
+        self.balance_spines();
+        self.debug_validate();
+    }
+
+    /// Adds `value` at the start by rebuilding only the left spine.
+    pub fn prepend(&mut self, value: T) {
+        if value.is_empty() {
+            return;
+        }
+
+        self.anchors.insert_text(0, value.len());
+
+        self.root = prepend_rec(&self.root.0, value);
+        self.balance_spines();
+        self.debug_validate();
+    }
+
+    /// Concatenates `other` without copying either tree. Anchors of `other`
+    /// are moved over.
+    pub fn append_rope(&mut self, other: Rope<T>) {
+        let len = self.root.measure();
+        self.anchors.append(other.anchors, len);
+
+        self.root = match (self.root.is_not_none(), other.root.is_not_none()) {
+            (_, false) => return,
+            (false, true) => other.root,
+            (true, true) => self.root.clone().concat(other.root),
+        };
+
+        self.balance_spines();
+        self.debug_validate();
+    }
+
+    /// Replaces every range with its value in a single pass over the leafs.
+    ///
+    /// Ranges refer to offsets before any edit and are clamped to the rope.
+    /// Edits are applied in order of their start, a range overlapping an
+    /// earlier one is cut to start after it. Untouched leafs are shared with
+    /// the old tree and the result is balanced.
+    pub fn apply_edits(&mut self, mut edits: Vec<(Range<usize>, T)>) {
+        edits.sort_by_key(|(range, _)| range.start);
+
+        let total = self.root.measure();
+        let mut prev_end = 0;
+
+        for (range, _) in edits.iter_mut() {
+            let start = range.start.clamp(prev_end, total);
+            let end = range.end.clamp(start, total);
+
+            *range = start..end;
+            prev_end = end;
+        }
+
+        for (range, value) in edits.iter().rev() {
+            self.anchors.delete_text(range.start, range.len());
+            self.anchors.insert_text(range.start, value.len());
+        }
+
+        let mut leafs = vec![];
+        let mut cursor = LeafCursor {
+            leafs: self.root.iter(),
+            current: None,
+            offset: 0,
+        };
+
+        for (range, value) in edits {
+            cursor.advance(range.start, Some(&mut leafs));
+            cursor.advance(range.end, None);
+
+            if !value.is_empty() {
+                leafs.push(Arc::new(RopeNodeType::Leaf(Leaf { value })).into());
+            }
+        }
+
+        cursor.advance(usize::MAX, Some(&mut leafs));
+
+        self.root = RopeNode::from_iter(leafs);
+        self.debug_validate();
+    }
+
+    fn balance_spines(&mut self) {
+        let deepest = spine_depth(&self.root.0, true).max(spine_depth(&self.root.0, false));
+
+        if deepest > MAX_DEPTH / 2 {
+            self.root = self.root.rebalance();
+        }
+    }
+}
+
+/// Walks the leafs of a rope, copying or skipping them up to an offset.
+struct LeafCursor<T> {
+    leafs: RopeIter<T>,
+    /// Partially consumed leaf and how much of it was consumed.
+    current: Option<(RopeNode<T>, usize)>,
+    offset: usize,
+}
+
+impl<T: Chunk> LeafCursor<T> {
+    fn advance(&mut self, target: usize, mut out: Option<&mut Vec<RopeNode<T>>>) {
+        while self.offset < target {
+            let (node, consumed) = match self.current.take() {
+                Some(current) => current,
+                None => match self.leafs.next() {
+                    Some(node) => (node, 0),
+                    None => return,
+                },
+            };
+
+            let Some(leaf) = node.map_leaf() else {
+                continue;
+            };
+
+            let step = (leaf.value.len() - consumed).min(target - self.offset);
+
+            if let Some(out) = out.as_deref_mut() {
+                match (consumed, step) {
+                    (0, step) if step == leaf.value.len() => out.push(node.clone()),
+                    (_, 0) => (),
+                    _ => out.push(
+                        Arc::new(RopeNodeType::Leaf(Leaf {
+                            value: leaf.value.slice(consumed..consumed + step),
+                        }))
+                        .into(),
+                    ),
+                }
+            }
+
+            self.offset += step;
+
+            if consumed + step < leaf.value.len() {
+                self.current = Some((node, consumed + step));
+            }
+        }
+    }
+}
+
+fn leaf<T>(value: T) -> RopeNode<T> {
+    Arc::new(RopeNodeType::Leaf(Leaf { value })).into()
+}
+
+fn append_rec<T: Chunk>(node: &Arc<RopeNodeType<T>>, value: T) -> RopeNode<T> {
+    match node.as_ref() {
+        RopeNodeType::Node(n) => Arc::new(RopeNodeType::Node(Node {
+            left: n.left.clone(),
+            right: append_rec(&n.right.0, value),
+            weight: n.weight,
+        }))
+        .into(),
+        RopeNodeType::Leaf(l) if l.value.len() + value.len() <= MAX_LEAF_LEN => {
+            leaf(l.value.concat(&value))
+        }
+        RopeNodeType::Leaf(l) => Arc::new(RopeNodeType::Node(Node {
+            left: Arc::clone(node).into(),
+            right: leaf(value),
+            weight: l.value.len(),
+        }))
+        .into(),
+        RopeNodeType::None => leaf(value),
+    }
+}
+
+fn prepend_rec<T: Chunk>(node: &Arc<RopeNodeType<T>>, value: T) -> RopeNode<T> {
+    match node.as_ref() {
+        RopeNodeType::Node(n) => {
+            let weight = n.weight + value.len();
+
+            Arc::new(RopeNodeType::Node(Node {
+                left: prepend_rec(&n.left.0, value),
+                right: n.right.clone(),
+                weight,
+            }))
+            .into()
+        }
+        RopeNodeType::Leaf(l) if l.value.len() + value.len() <= MAX_LEAF_LEN => {
+            leaf(value.concat(&l.value))
+        }
+        RopeNodeType::Leaf(_) => Arc::new(RopeNodeType::Node(Node {
+            weight: value.len(),
+            left: leaf(value),
+            right: Arc::clone(node).into(),
+        }))
+        .into(),
+        RopeNodeType::None => leaf(value),
+    }
+}
+
+fn spine_depth<T>(node: &RopeNodeType<T>, left: bool) -> usize {
+    match node {
+        RopeNodeType::Node(n) if left => spine_depth(&n.left.0, left) + 1,
+        RopeNodeType::Node(n) => spine_depth(&n.right.0, left) + 1,
+        RopeNodeType::Leaf(_) | RopeNodeType::None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rope::rope_anchor::Bias;
+
+    #[test]
+    fn append_prepend_test() {
+        let mut rope = Rope::from("hello");
+        let anchor = rope.create_anchor(5, Bias::Right);
+
+        rope.append(String::from(" world"));
+        rope.prepend(String::from(">> "));
+
+        assert_eq!(rope, ">> hello world");
+        assert_eq!(rope.anchor_offset(anchor), Some(14));
+        assert_eq!(rope.iter().count(), 2);
+
+        let mut rope: Rope = Rope::default();
+
+        for i in 0..2000 {
+            rope.append(format!("{i:>4}"));
+        }
+
+        assert_eq!(rope.len(), 8000);
+        assert!(spine_depth(&rope.root.0, false) <= MAX_DEPTH / 2);
+        assert_eq!(rope.validate_structure(), Ok(()));
+    }
+
+    #[test]
+    fn append_rope_test() {
+        let mut first = Rope::from("hello ");
+        let mut second = Rope::from("world");
+        let anchor = second.create_anchor(2, Bias::Left);
+
+        first.append_rope(second);
+        first.append_rope(Rope::default());
+
+        assert_eq!(first, "hello world");
+        assert_eq!(first.anchor_offset(anchor), Some(8));
+
+        let mut empty: Rope = Rope::default();
+        empty.append_rope(first);
+
+        assert_eq!(empty, "hello world");
+    }
+
+    #[test]
+    fn apply_edits_test() {
+        let mut rope = Rope::from_iter(["let a = 1;", " let b = 2;", " a + b"].map(leaf_of));
+        let left = rope.create_anchor(15, Bias::Left);
+        let right = rope.create_anchor(27, Bias::Right);
+
+        rope.apply_edits(vec![
+            (27..27, String::from(";")),
+            (4..5, String::from("first")),
+            (15..16, String::from("second")),
+            (8..9, String::from("10")),
+            (22..23, String::from("first")),
+            (0..0, String::new()),
+        ]);
+
+        assert_eq!(rope, "let first = 10; let second = 2; first + b;");
+        assert_eq!(rope.anchor_offset(left), Some(20));
+        assert_eq!(rope.anchor_offset(right), Some(42));
+
+        rope.apply_edits(vec![(0..100, String::from("x")), (5..8, String::from("y"))]);
+
+        assert_eq!(rope, "xy");
+    }
+
+    fn leaf_of(value: &str) -> RopeNode {
+        leaf(String::from(value))
+    }
+}
@@ -1,35 +1,33 @@
 use super::rope_node::{RopeNode, RopeNodeType};
-use std::rc::Rc;
+use std::sync::Arc;
 
-pub struct RopeIter {
-    pub nodes_stack: Vec<Rc<RopeNodeType>>,
+pub struct RopeIter<T = String> {
+    pub nodes_stack: Vec<Arc<RopeNodeType<T>>>,
 }
 
-impl RopeIter {
-    fn collect_parent_right_nodes(&mut self, parent: &RopeNodeType) {
+impl<T> RopeIter<T> {
+    fn collect_parent_right_nodes(&mut self, parent: &RopeNodeType<T>) {
         match parent {
             RopeNodeType::Node(parent_node) => match parent_node.right.0.as_ref() {
                 RopeNodeType::Node(right_node) => {
-                    self.nodes_stack.push(Rc::clone(&parent_node.right.0));
-                    let mut cur_node = Rc::clone(&right_node.left.0);
+                    self.nodes_stack.push(Arc::clone(&parent_node.right.0));
+                    let mut cur_node = Arc::clone(&right_node.left.0);
 
                     loop {
                         match cur_node.as_ref() {
                             RopeNodeType::Node(node) => {
-                                self.nodes_stack.push(Rc::clone(&cur_node));
-                                cur_node = Rc::clone(&node.left.0);
+                                self.nodes_stack.push(Arc::clone(&cur_node));
+                                cur_node = Arc::clone(&node.left.0);
                             }
                             RopeNodeType::Leaf(_) => {
-                                self.nodes_stack.push(Rc::clone(&cur_node));
+                                self.nodes_stack.push(Arc::clone(&cur_node));
                                 break;
                             }
                             RopeNodeType::None => break,
                         }
                     }
                 }
-                RopeNodeType::Leaf(_) => self
-                    .nodes_stack
-                    .push(Rc::clone(&parent_node.right.0).into()),
+                RopeNodeType::Leaf(_) => self.nodes_stack.push(Arc::clone(&parent_node.right.0)),
                 RopeNodeType::None => (),
             },
             RopeNodeType::Leaf(_) | RopeNodeType::None => (),
@@ -37,17 +35,16 @@ impl RopeIter {
     }
 }
 
-impl Iterator for RopeIter {
-    type Item = RopeNode;
+impl<T> Iterator for RopeIter<T> {
+    type Item = RopeNode<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.nodes_stack.pop() {
                 Some(rope_node) => match rope_node.as_ref() {
                     RopeNodeType::Leaf(_) => {
-                        match self.nodes_stack.pop() {
-                            Some(parent) => self.collect_parent_right_nodes(parent.as_ref()),
-                            None => (),
+                        if let Some(parent) = self.nodes_stack.pop() {
+                            self.collect_parent_right_nodes(parent.as_ref())
                         };
 
                         return Some(rope_node.into());
@@ -0,0 +1,262 @@
+use std::{ops::Range, sync::Arc};
+
+use super::{
+    rope::Rope,
+    rope_diff::DiffHunk,
+    rope_node::{Leaf, RopeNode, RopeNodeType},
+};
+
+pub const OURS_MARKER: &str = "<<<<<<< ours\n";
+pub const SEPARATOR_MARKER: &str = "=======\n";
+pub const THEIRS_MARKER: &str = ">>>>>>> theirs\n";
+
+/// Outcome of [`merge3`]. Conflicting regions are written into `rope`
+/// surrounded by conflict markers and listed in `conflicts`.
+#[derive(Debug)]
+pub struct MergeResult {
+    pub rope: Rope,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Byte range of the marked region in the merged rope.
+    pub range: Range<usize>,
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Ours,
+    Theirs,
+}
+
+/// Line based three-way merge. Hunks changed on only one side, or changed
+/// identically on both, are applied. Overlapping hunks become conflicts.
+pub fn merge3(base: &Rope, ours: &Rope, theirs: &Rope) -> MergeResult {
+    let base_text = text(base);
+    let ours_text = text(ours);
+    let theirs_text = text(theirs);
+
+    let base_lines: Vec<&str> = base_text.split_inclusive('\n').collect();
+    let ours_lines: Vec<&str> = ours_text.split_inclusive('\n').collect();
+    let theirs_lines: Vec<&str> = theirs_text.split_inclusive('\n').collect();
+
+    let mut hunks: Vec<(Side, DiffHunk)> = base
+        .diff(ours)
+        .hunks
+        .into_iter()
+        .map(|h| (Side::Ours, h))
+        .chain(
+            base.diff(theirs)
+                .hunks
+                .into_iter()
+                .map(|h| (Side::Theirs, h)),
+        )
+        .collect();
+    hunks.sort_by_key(|(_, h)| (h.old_lines.start, h.old_lines.end));
+
+    let mut output: Vec<String> = vec![];
+    let mut conflicts = vec![];
+    let mut offset = 0;
+
+    let mut base_line = 0;
+    let mut ours_delta: isize = 0;
+    let mut theirs_delta: isize = 0;
+
+    let mut hunks = hunks.into_iter().peekable();
+
+    while let Some((side, first)) = hunks.next() {
+        let mut chunk = vec![(side, first)];
+        let mut chunk_lines = chunk[0].1.old_lines.clone();
+
+        while let Some((side, next)) = hunks.next_if(|(_, next)| {
+            next.old_lines.start < chunk_lines.end
+                || (next.old_lines.start == chunk_lines.end
+                    && (next.old_lines.is_empty() || chunk_lines.is_empty()))
+        }) {
+            chunk_lines.end = chunk_lines.end.max(next.old_lines.end);
+            chunk.push((side, next));
+        }
+
+        let unchanged = base_lines[base_line..chunk_lines.start].concat();
+        offset += unchanged.len();
+        output.push(unchanged);
+        base_line = chunk_lines.end;
+
+        let ours_range = side_range(&chunk, Side::Ours, &chunk_lines, ours_delta);
+        let theirs_range = side_range(&chunk, Side::Theirs, &chunk_lines, theirs_delta);
+
+        ours_delta += ours_range.len() as isize - chunk_lines.len() as isize;
+        theirs_delta += theirs_range.len() as isize - chunk_lines.len() as isize;
+
+        let ours_part = ours_lines[ours_range].concat();
+        let theirs_part = theirs_lines[theirs_range].concat();
+
+        let has_ours = chunk.iter().any(|(s, _)| *s == Side::Ours);
+        let has_theirs = chunk.iter().any(|(s, _)| *s == Side::Theirs);
+
+        let merged = match (has_ours, has_theirs) {
+            (true, false) => ours_part,
+            (false, true) => theirs_part,
+            _ if ours_part == theirs_part => ours_part,
+            _ => {
+                let marked = format!(
+                    "{OURS_MARKER}{}{SEPARATOR_MARKER}{}{THEIRS_MARKER}",
+                    with_line_end(&ours_part),
+                    with_line_end(&theirs_part),
+                );
+
+                conflicts.push(MergeConflict {
+                    range: offset..offset + marked.len(),
+                    base: base_lines[chunk_lines].concat(),
+                    ours: ours_part,
+                    theirs: theirs_part,
+                });
+
+                marked
+            }
+        };
+
+        offset += merged.len();
+        output.push(merged);
+    }
+
+    output.push(base_lines[base_line..].concat());
+
+    let rope = Rope::from_iter(
+        output
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .map(|value| RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf { value })))),
+    );
+
+    MergeResult { rope, conflicts }
+}
+
+/// Line range of one side covering the base lines of a chunk. `delta` is
+/// the line count difference the side accumulated before the chunk.
+fn side_range(
+    chunk: &[(Side, DiffHunk)],
+    side: Side,
+    base_lines: &Range<usize>,
+    delta: isize,
+) -> Range<usize> {
+    let hunks: Vec<&DiffHunk> = chunk
+        .iter()
+        .filter(|(s, _)| *s == side)
+        .map(|(_, h)| h)
+        .collect();
+
+    match (hunks.first(), hunks.last()) {
+        (Some(first), Some(last)) => {
+            first.new_lines.start - (first.old_lines.start - base_lines.start)
+                ..last.new_lines.end + (base_lines.end - last.old_lines.end)
+        }
+        _ => {
+            (base_lines.start as isize + delta) as usize..(base_lines.end as isize + delta) as usize
+        }
+    }
+}
+
+fn with_line_end(value: &str) -> String {
+    match value.is_empty() || value.ends_with('\n') {
+        true => value.to_string(),
+        false => format!("{value}\n"),
+    }
+}
+
+fn text(rope: &Rope) -> String {
+    rope.iter()
+        .filter_map(|n| n.map_leaf().map(|l| l.value.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rope(value: &str) -> Rope {
+        Rope::new(
+            Arc::new(RopeNodeType::Leaf(Leaf {
+                value: String::from(value),
+            }))
+            .into(),
+        )
+    }
+
+    #[test]
+    fn merge3_clean_test() {
+        let base = rope("one\ntwo\nthree\nfour\n");
+        let ours = rope("one\n2\nthree\nfour\n");
+        let theirs = rope("one\ntwo\nthree\nfour\nfive\n");
+
+        let result = merge3(&base, &ours, &theirs);
+
+        assert!(result.is_clean());
+        assert_eq!(text(&result.rope), "one\n2\nthree\nfour\nfive\n");
+    }
+
+    #[test]
+    fn merge3_same_change_test() {
+        let base = rope("one\ntwo\nthree\n");
+        let ours = rope("one\n2\nthree\n");
+        let theirs = rope("one\n2\nthree\n");
+
+        let result = merge3(&base, &ours, &theirs);
+
+        assert!(result.is_clean());
+        assert_eq!(text(&result.rope), "one\n2\nthree\n");
+    }
+
+    #[test]
+    fn merge3_conflict_test() {
+        let base = rope("one\ntwo\nthree\n");
+        let ours = rope("zero\none\n2\nthree\n");
+        let theirs = rope("one\nTWO\nthree\n");
+
+        let result = merge3(&base, &ours, &theirs);
+        let merged = text(&result.rope);
+
+        assert_eq!(
+            merged,
+            "zero\none\n<<<<<<< ours\n2\n=======\nTWO\n>>>>>>> theirs\nthree\n"
+        );
+        assert_eq!(
+            result.conflicts,
+            vec![MergeConflict {
+                range: 9..51,
+                base: String::from("two\n"),
+                ours: String::from("2\n"),
+                theirs: String::from("TWO\n"),
+            }]
+        );
+        assert_eq!(
+            &merged[result.conflicts[0].range.clone()],
+            "<<<<<<< ours\n2\n=======\nTWO\n>>>>>>> theirs\n"
+        );
+    }
+
+    #[test]
+    fn merge3_missing_line_end_test() {
+        let base = rope("one\ntwo");
+        let ours = rope("one\n2");
+        let theirs = rope("one\nTWO");
+
+        let result = merge3(&base, &ours, &theirs);
+
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(
+            text(&result.rope),
+            "one\n<<<<<<< ours\n2\n=======\nTWO\n>>>>>>> theirs\n"
+        );
+    }
+}
@@ -1,27 +1,27 @@
-use super::rope_iter::RopeIter;
+use super::{rope_chunk::Chunk, rope_iter::RopeIter};
 use crate::helpers::fibonacci_seq::get_fibonacci_number;
-use std::{cmp, fmt::Display, rc::Rc};
+use std::{cmp, fmt::Display, sync::Arc};
 
 #[derive(Debug)]
-pub enum RopeNodeType {
-    Node(Node),
-    Leaf(Leaf),
+pub enum RopeNodeType<T = String> {
+    Node(Node<T>),
+    Leaf(Leaf<T>),
     None,
 }
 
 #[derive(Debug)]
-pub struct Node {
-    pub left: RopeNode,
-    pub right: RopeNode,
+pub struct Node<T = String> {
+    pub left: RopeNode<T>,
+    pub right: RopeNode<T>,
     pub weight: usize,
 }
 
 #[derive(Debug)]
-pub struct Leaf {
-    pub value: String,
+pub struct Leaf<T = String> {
+    pub value: T,
 }
 
-impl Display for RopeNodeType {
+impl<T: Display> Display for RopeNodeType<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             RopeNodeType::Node(node) => {
@@ -33,116 +33,141 @@ impl Display for RopeNodeType {
     }
 }
 
+impl<T: Chunk> RopeNodeType<T> {
+    /// Length read from the weights along the right spine, O(depth).
+    pub fn measure(&self) -> usize {
+        match self {
+            RopeNodeType::Node(node) => node.weight + node.right.0.measure(),
+            RopeNodeType::Leaf(leaf) => leaf.value.len(),
+            RopeNodeType::None => 0,
+        }
+    }
+}
+
 #[derive(Debug)]
-pub struct RopeNode(pub Rc<RopeNodeType>);
+pub struct RopeNode<T = String>(pub Arc<RopeNodeType<T>>);
 
-impl From<Rc<RopeNodeType>> for RopeNode {
-    fn from(value: Rc<RopeNodeType>) -> Self {
+impl<T> From<Arc<RopeNodeType<T>>> for RopeNode<T> {
+    fn from(value: Arc<RopeNodeType<T>>) -> Self {
         RopeNode(value)
     }
 }
 
-impl Display for RopeNode {
+impl<T: Display> Display for RopeNode<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
     }
 }
 
-impl RopeNode {
-    pub fn concat(self, s2: RopeNode) -> RopeNode {
-        let weight = self
-            .iter()
-            .map(|n| {
-                n.map_leaf()
-                    .expect("error while iterating leafs. None leaf node found")
-                    .value
-                    .len()
-            })
-            .sum();
-
-        RopeNode(Rc::new(RopeNodeType::Node(Node {
-            left: self,
-            right: RopeNode::from(s2),
+impl<T: Chunk> RopeNode<T> {
+    pub fn concat(self, s2: RopeNode<T>) -> RopeNode<T> {
+        if !self.is_not_none() && !s2.is_not_none() {
+            return self;
+        }
+
+        // a node keeps its only child on the left
+        let (left, right) = match (self.0.as_ref(), s2.0.as_ref()) {
+            (RopeNodeType::None, RopeNodeType::Node(_)) => return s2,
+            (RopeNodeType::None, _) => (s2, self),
+            _ => (self, s2),
+        };
+        let weight = left.measure();
+
+        RopeNode(Arc::new(RopeNodeType::Node(Node {
+            left,
+            right,
             weight,
         })))
     }
 
-    pub fn iter(&self) -> RopeIter {
-        let mut nodes_stack: Vec<Rc<RopeNodeType>> = vec![];
-        let mut cur_node = Rc::clone(&self.0);
+    pub fn iter(&self) -> RopeIter<T> {
+        let mut nodes_stack: Vec<Arc<RopeNodeType<T>>> = vec![];
+        let mut cur_node = Arc::clone(&self.0);
 
         loop {
             if let RopeNodeType::None = cur_node.as_ref() {
                 break;
             }
 
-            nodes_stack.push(Rc::clone(&cur_node));
+            nodes_stack.push(Arc::clone(&cur_node));
 
             cur_node = match cur_node.as_ref() {
-                RopeNodeType::Node(node) => Rc::clone(&node.left.0).into(),
-                RopeNodeType::Leaf(_) | RopeNodeType::None => Rc::new(RopeNodeType::None),
+                RopeNodeType::Node(node) => Arc::clone(&node.left.0),
+                RopeNodeType::Leaf(_) | RopeNodeType::None => Arc::new(RopeNodeType::None),
             }
         }
 
         RopeIter { nodes_stack }
     }
 
-    pub fn get_char(&self, index: usize) -> Option<char> {
-        self.get_char_rec(index, &self.0.as_ref())
+    /// Element at `index`, a byte for string ropes.
+    pub fn get(&self, index: usize) -> Option<&T::Item> {
+        let mut node = self.0.as_ref();
+        let mut index = index;
+
+        loop {
+            match node {
+                RopeNodeType::Node(n) if index >= n.weight => {
+                    index -= n.weight;
+                    node = n.right.0.as_ref();
+                }
+                RopeNodeType::Node(n) => node = n.left.0.as_ref(),
+                RopeNodeType::Leaf(leaf) => return leaf.value.get(index),
+                RopeNodeType::None => return None,
+            }
+        }
     }
 
-    pub fn split(&self, index: usize) -> (RopeNode, RopeNode) {
+    pub fn split(&self, index: usize) -> (RopeNode<T>, RopeNode<T>) {
         match index {
             0 => (
-                Rc::new(RopeNodeType::None).into(),
-                Rc::clone(&self.0).into(),
+                Arc::new(RopeNodeType::None).into(),
+                Arc::clone(&self.0).into(),
             ),
             i if i >= self.len() => (
-                Rc::clone(&self.0).into(),
-                Rc::new(RopeNodeType::None).into(),
+                Arc::clone(&self.0).into(),
+                Arc::new(RopeNodeType::None).into(),
             ),
             _ => {
                 let mut cur_idx = 0;
-                let mut iter = self.iter();
+                let iter = self.iter();
 
                 let mut left_subtree = vec![];
                 let mut right_subtree = vec![];
 
-                loop {
-                    match iter.next() {
-                        Some(node) => {
-                            let str_part = &node.map_leaf().expect("leaf expected").value;
-
-                            let str_part_max_idx = cur_idx + str_part.len();
-
-                            match str_part_max_idx {
-                                i if cur_idx < index && i > index => {
-                                    left_subtree.push(
-                                        Rc::new(RopeNodeType::Leaf(Leaf {
-                                            value: str_part[0..index - cur_idx].to_string(),
-                                        }))
-                                        .into(),
-                                    );
-                                    right_subtree.push(
-                                        Rc::new(RopeNodeType::Leaf(Leaf {
-                                            value: str_part[index - cur_idx..].to_string(),
-                                        }))
-                                        .into(),
-                                    )
-                                }
-                                _ if cur_idx < index => {
-                                    left_subtree.push(Rc::clone(&node.0).into());
-                                }
-                                _ if cur_idx >= index => {
-                                    right_subtree.push(Rc::clone(&node.0).into());
-                                }
-                                _ => (),
-                            }
+                for node in iter {
+                    let Some(leaf) = node.map_leaf() else {
+                        continue;
+                    };
+                    let str_part = &leaf.value;
 
-                            cur_idx += str_part.len();
+                    let str_part_max_idx = cur_idx + str_part.len();
+
+                    match str_part_max_idx {
+                        i if cur_idx < index && i > index => {
+                            left_subtree.push(
+                                Arc::new(RopeNodeType::Leaf(Leaf {
+                                    value: str_part.slice(0..index - cur_idx),
+                                }))
+                                .into(),
+                            );
+                            right_subtree.push(
+                                Arc::new(RopeNodeType::Leaf(Leaf {
+                                    value: str_part.slice(index - cur_idx..str_part.len()),
+                                }))
+                                .into(),
+                            )
                         }
-                        None => break,
-                    };
+                        _ if cur_idx < index => {
+                            left_subtree.push(Arc::clone(&node.0).into());
+                        }
+                        _ if cur_idx >= index => {
+                            right_subtree.push(Arc::clone(&node.0).into());
+                        }
+                        _ => (),
+                    }
+
+                    cur_idx += str_part.len();
                 }
 
                 (
@@ -153,58 +178,67 @@ impl RopeNode {
         }
     }
 
-    pub fn substring(&self, start: usize, len: usize) -> RopeNode {
-        let mut leafs = vec![];
-        let mut start_idx = start;
-        let mut len_left = len;
+    /// Copy of `len` elements from `start`.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds or doesn't start and end on a char
+    /// boundary, see [`RopeNode::check_range`].
+    pub fn substring(&self, start: usize, len: usize) -> RopeNode<T> {
+        let range = self.expect_range(start, len);
 
-        let mut iter = self.iter();
+        let mut leafs = vec![];
+        let mut start_idx = range.start;
+        let mut len_left = range.len();
 
-        loop {
-            if len_left <= 0 {
+        for node in self.iter() {
+            if len_left == 0 {
                 break;
             }
 
-            match iter.next() {
-                Some(node) => {
-                    let str_part = &node.map_leaf().expect("leaf expected").value;
+            let Some(leaf) = node.map_leaf() else {
+                continue;
+            };
+            let part = &leaf.value;
 
-                    if str_part.len() == 0 {
-                        continue;
-                    }
+            if start_idx >= part.len() {
+                start_idx -= part.len();
+                continue;
+            }
 
-                    let str_part_max_idx = str_part.len() - 1;
+            let end_idx = part.len().min(start_idx + len_left);
 
-                    match str_part_max_idx {
-                        _ if start_idx == 0 && len_left >= str_part.len() - 1 => {
-                            leafs.push(Rc::clone(&node.0).into());
-                            len_left -= str_part.len();
-                        }
-                        i if i > start_idx => {
-                            let new_value: String =
-                                str_part.chars().skip(start_idx).take(len_left).collect();
-
-                            start_idx = 0;
-                            len_left -= new_value.len();
+            match (start_idx, end_idx) {
+                (0, end_idx) if end_idx == part.len() => leafs.push(Arc::clone(&node.0).into()),
+                _ => leafs.push(
+                    Arc::new(RopeNodeType::Leaf(Leaf {
+                        value: part.slice(start_idx..end_idx),
+                    }))
+                    .into(),
+                ),
+            }
 
-                            if new_value.len() > 0 {
-                                leafs.push(
-                                    Rc::new(RopeNodeType::Leaf(Leaf { value: new_value })).into(),
-                                );
-                            }
-                        }
-                        _ => start_idx -= str_part.len(),
-                    }
-                }
-                None => break,
-            };
+            len_left -= end_idx - start_idx;
+            start_idx = 0;
         }
 
         RopeNode::from_iter(leafs)
     }
 
     pub fn len(&self) -> usize {
-        self.iter().map(|n| n.map_leaf().unwrap().value.len()).sum()
+        self.iter()
+            .filter_map(|n| n.map_leaf().map(|l| l.value.len()))
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Like [`RopeNode::len`], but trusts the weights instead of visiting
+    /// every leaf.
+    pub fn measure(&self) -> usize {
+        self.0.measure()
     }
 
     pub fn get_depth(&self) -> usize {
@@ -219,12 +253,12 @@ impl RopeNode {
         }
     }
 
-    pub fn insert(&self, index: usize, value: String) -> RopeNode {
-        let new_leaf = RopeNode(Rc::new(RopeNodeType::Leaf(Leaf { value })));
+    pub fn insert(&self, index: usize, value: T) -> RopeNode<T> {
+        let new_leaf = RopeNode(Arc::new(RopeNodeType::Leaf(Leaf { value })));
 
         match index {
-            0 => new_leaf.concat(Rc::clone(&self.0).into()),
-            i if i >= self.len() => RopeNode(Rc::clone(&self.0)).concat(new_leaf),
+            0 => new_leaf.concat(Arc::clone(&self.0).into()),
+            i if i >= self.len() => RopeNode(Arc::clone(&self.0)).concat(new_leaf),
             _ => {
                 let (left, right) = self.split(index);
 
@@ -233,7 +267,7 @@ impl RopeNode {
         }
     }
 
-    pub fn rebalance(&self) -> RopeNode {
+    pub fn rebalance(&self) -> RopeNode<T> {
         RopeNode::from_iter(self.iter())
     }
 
@@ -244,7 +278,7 @@ impl RopeNode {
         self.iter().count() >= min_length
     }
 
-    pub fn map_leaf(&self) -> Option<&Leaf> {
+    pub fn map_leaf(&self) -> Option<&Leaf<T>> {
         match self.0.as_ref() {
             RopeNodeType::Leaf(l) => Some(l),
             RopeNodeType::Node(_) | RopeNodeType::None => None,
@@ -252,18 +286,28 @@ impl RopeNode {
     }
 
     pub fn is_not_none(&self) -> bool {
-        match self.0.as_ref() {
-            RopeNodeType::None => false,
-            _ => true,
-        }
+        !matches!(self.0.as_ref(), RopeNodeType::None)
     }
 
-    pub fn delete(&self, start: usize, len: usize) -> RopeNode {
-        let (left, _) = self.split(start);
-        let (_, right) = self.split(start + len);
+    /// Tree without the `len` elements from `start`.
+    ///
+    /// # Panics
+    ///
+    /// Like [`RopeNode::substring`].
+    pub fn delete(&self, start: usize, len: usize) -> RopeNode<T> {
+        let range = self.expect_range(start, len);
+
+        let (left, _) = self.split(range.start);
+        let (_, right) = self.split(range.end);
 
         left.concat(right)
     }
+}
+
+impl RopeNode {
+    pub fn get_char(&self, index: usize) -> Option<char> {
+        self.get_char_rec(index, self.0.as_ref())
+    }
 
     fn get_char_rec(&self, index: usize, node: &RopeNodeType) -> Option<char> {
         match node {
@@ -280,23 +324,23 @@ impl RopeNode {
     }
 }
 
-impl FromIterator<RopeNode> for RopeNode {
-    fn from_iter<T: IntoIterator<Item = RopeNode>>(iter: T) -> Self {
+impl<T: Chunk> FromIterator<RopeNode<T>> for RopeNode<T> {
+    fn from_iter<I: IntoIterator<Item = RopeNode<T>>>(iter: I) -> Self {
         let mut nodes_with_weights = vec![];
 
         for node in iter {
             match node.0.as_ref() {
                 RopeNodeType::Leaf(leaf) => {
-                    nodes_with_weights.push((Rc::clone(&node.0), leaf.value.len(), 0 as usize))
+                    nodes_with_weights.push((Arc::clone(&node.0), leaf.value.len(), 0_usize))
                 }
                 RopeNodeType::Node(_) | RopeNodeType::None => (),
             }
         }
 
         loop {
-            match nodes_with_weights.len() {
-                0 => return RopeNode(Rc::new(RopeNodeType::None)),
-                1 => return RopeNode(nodes_with_weights.pop().unwrap().0),
+            match nodes_with_weights.as_slice() {
+                [] => return RopeNode(Arc::new(RopeNodeType::None)),
+                [(node, _, _)] => return RopeNode(Arc::clone(node)),
                 _ => {
                     let nodes_num = (nodes_with_weights.len() as f32 / 2.0).ceil() as usize;
 
@@ -306,12 +350,12 @@ impl FromIterator<RopeNode> for RopeNode {
                             let right = nodes_with_weights.get(2 * i + 1);
 
                             match (left, right) {
-                                (None, None) => (Rc::new(RopeNodeType::None), 0, 0),
-                                (None, Some(n)) | (Some(n), None) => (Rc::clone(&n.0), n.1, n.2),
+                                (None, None) => (Arc::new(RopeNodeType::None), 0, 0),
+                                (None, Some(n)) | (Some(n), None) => (Arc::clone(&n.0), n.1, n.2),
                                 (Some(left), Some(right)) => (
-                                    Rc::new(RopeNodeType::Node(Node {
-                                        left: Rc::clone(&left.0).into(),
-                                        right: Rc::clone(&right.0).into(),
+                                    Arc::new(RopeNodeType::Node(Node {
+                                        left: Arc::clone(&left.0).into(),
+                                        right: Arc::clone(&right.0).into(),
                                         weight: left.1 + left.2,
                                     })),
                                     left.1 + left.2,
@@ -319,7 +363,7 @@ impl FromIterator<RopeNode> for RopeNode {
                                 ),
                             }
                         })
-                        .filter(|n| RopeNode::from(Rc::clone(&n.0)).is_not_none())
+                        .filter(|n| RopeNode::from(Arc::clone(&n.0)).is_not_none())
                         .collect();
                 }
             };
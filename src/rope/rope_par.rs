@@ -0,0 +1,347 @@
+use std::{num::NonZero, sync::Arc, thread};
+
+use super::{
+    rope::Rope,
+    rope_chunk::Chunk,
+    rope_edit::MAX_LEAF_LEN,
+    rope_node::{Leaf, RopeNode, RopeNodeType},
+};
+
+/// Bytes every thread gets at least. Below that spawning a thread costs
+/// more than it saves, so small ropes are processed on the calling thread.
+pub const MIN_THREAD_LEN: usize = 256 * 1024;
+
+/// Leafs of a rope, processed on scoped threads. See [`Rope::par_chunks`].
+pub struct ParChunks<T> {
+    leafs: Vec<RopeNode<T>>,
+    len: usize,
+}
+
+impl<T: Chunk + Send + Sync> ParChunks<T> {
+    /// Maps every chunk, results are in rope order.
+    pub fn map<R, F>(&self, f: F) -> Vec<R>
+    where
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        scoped_map(&self.leafs, threads(self.len), |_, leafs| {
+            leafs
+                .iter()
+                .filter_map(|n| n.map_leaf())
+                .map(|leaf| f(&leaf.value))
+                .collect::<Vec<R>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    pub fn for_each<F>(&self, f: F)
+    where
+        F: Fn(&T) + Sync,
+    {
+        self.map(f);
+    }
+}
+
+impl<T: Chunk + Send + Sync> Rope<T> {
+    pub fn par_chunks(&self) -> ParChunks<T> {
+        let leafs: Vec<RopeNode<T>> = self.iter().collect();
+        let len = leafs
+            .iter()
+            .filter_map(|n| n.map_leaf())
+            .map(|leaf| leaf.value.len())
+            .sum();
+
+        ParChunks { leafs, len }
+    }
+}
+
+impl Rope {
+    /// Byte offsets of every occurrence of `pattern`, overlapping ones
+    /// included. Every thread searches a run of leafs plus enough of the
+    /// following text to catch matches crossing into the next run.
+    pub fn par_find_all(&self, pattern: &str) -> Vec<usize> {
+        self.find_all_on(pattern, None)
+    }
+
+    /// Searches on `threads` threads, or as many as [`threads`] picks.
+    fn find_all_on(&self, pattern: &str, threads: Option<usize>) -> Vec<usize> {
+        if pattern.is_empty() {
+            return vec![];
+        }
+
+        let threads = threads.unwrap_or_else(|| self::threads(self.len()));
+
+        if threads <= 1 {
+            return find_overlapping(&self.to_string(), pattern);
+        }
+
+        let mut offset = 0;
+        let leafs: Vec<(usize, RopeNode)> = self
+            .iter()
+            .map(|node| {
+                let start = offset;
+                offset += node.map_leaf().map_or(0, |l| l.value.len());
+
+                (start, node)
+            })
+            .collect();
+
+        let groups = scoped_map(&leafs, threads, |first, group| {
+            let Some((start, _)) = group.first() else {
+                return vec![];
+            };
+
+            let following = &leafs[first + group.len()..];
+
+            let mut text: String = group
+                .iter()
+                .filter_map(|(_, n)| n.map_leaf().map(|l| l.value.as_str()))
+                .collect();
+            let group_len = text.len();
+
+            for (_, node) in following {
+                if text.len() >= group_len + pattern.len() - 1 {
+                    break;
+                }
+
+                if let Some(leaf) = node.map_leaf() {
+                    text.push_str(&leaf.value);
+                }
+            }
+
+            find_overlapping(&text, pattern)
+                .into_iter()
+                .filter(|found| *found < group_len)
+                .map(|found| start + found)
+                .collect()
+        });
+
+        groups.into_iter().flatten().collect()
+    }
+
+    /// Number of lines, one more than the number of `\n`.
+    pub fn par_line_count(&self) -> usize {
+        let newlines: usize = self
+            .par_chunks()
+            .map(|value| value.matches('\n').count())
+            .into_iter()
+            .sum();
+
+        newlines + 1
+    }
+
+    /// Cuts `value` into leafs of at most [`MAX_LEAF_LEN`] bytes and builds a
+    /// balanced subtree per thread, which are then joined.
+    pub fn par_from_str(value: &str) -> Rope {
+        let mut chunks = vec![];
+        let mut rest = value;
+
+        while !rest.is_empty() {
+            let mut end = rest.len().min(MAX_LEAF_LEN);
+
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            let (chunk, tail) = rest.split_at(end);
+            chunks.push(chunk);
+            rest = tail;
+        }
+
+        let mut subtrees: Vec<RopeNode> = scoped_map(&chunks, threads(value.len()), |_, chunks| {
+            RopeNode::from_iter(chunks.iter().map(|chunk| {
+                RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf {
+                    value: chunk.to_string(),
+                })))
+            }))
+        });
+
+        while subtrees.len() > 1 {
+            let mut pairs = subtrees.into_iter();
+            subtrees = vec![];
+
+            while let Some(left) = pairs.next() {
+                match pairs.next() {
+                    Some(right) => subtrees.push(left.concat(right)),
+                    None => subtrees.push(left),
+                }
+            }
+        }
+
+        let rope = Rope::new(
+            subtrees
+                .pop()
+                .unwrap_or_else(|| Arc::new(RopeNodeType::None).into()),
+        );
+        rope.debug_validate();
+
+        rope
+    }
+}
+
+/// Threads worth spawning for `len` bytes: one per core, but no more than
+/// give every thread [`MIN_THREAD_LEN`] bytes.
+fn threads(len: usize) -> usize {
+    let cores = thread::available_parallelism().map_or(1, NonZero::get);
+
+    cores.min(len / MIN_THREAD_LEN).max(1)
+}
+
+/// Runs `f` on up to `threads` contiguous runs of `items`, passing the
+/// index of the first item of the run along. A single run is processed on
+/// the calling thread.
+fn scoped_map<I, R, F>(items: &[I], threads: usize, f: F) -> Vec<R>
+where
+    I: Sync,
+    R: Send,
+    F: Fn(usize, &[I]) -> R + Sync,
+{
+    if threads <= 1 {
+        return vec![f(0, items)];
+    }
+
+    let per_thread = items.len().div_ceil(threads).max(1);
+    let f = &f;
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(per_thread)
+            .enumerate()
+            .map(|(i, group)| scope.spawn(move || f(i * per_thread, group)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| match handle.join() {
+                Ok(result) => result,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    })
+}
+
+fn find_overlapping(text: &str, pattern: &str) -> Vec<usize> {
+    // without a border the matches can't overlap, and one searcher finds
+    // them all instead of one per match
+    if !has_border(pattern.as_bytes()) {
+        return text.match_indices(pattern).map(|(i, _)| i).collect();
+    }
+
+    let mut found = vec![];
+    let mut from = 0;
+
+    while let Some(idx) = text[from..].find(pattern) {
+        found.push(from + idx);
+        from += idx + text[from + idx..].chars().next().map_or(1, char::len_utf8);
+    }
+
+    found
+}
+
+/// Whether a proper prefix of `pattern` is also a suffix of it, from the
+/// failure function of Knuth-Morris-Pratt.
+fn has_border(pattern: &[u8]) -> bool {
+    let mut failure = vec![0; pattern.len()];
+    let mut border = 0;
+
+    for i in 1..pattern.len() {
+        while border > 0 && pattern[i] != pattern[border] {
+            border = failure[border - 1];
+        }
+
+        if pattern[i] == pattern[border] {
+            border += 1;
+        }
+
+        failure[i] = border;
+    }
+
+    border > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    fn text() -> String {
+        (0..5000)
+            .map(|i| format!("line {i} of the rope, ääh\n"))
+            .collect()
+    }
+
+    #[test]
+    fn send_sync_test() {
+        assert_send_sync::<Rope>();
+        assert_send_sync::<Rope<Vec<u64>>>();
+    }
+
+    #[test]
+    fn par_from_str_test() {
+        let text = text();
+        let rope = Rope::par_from_str(&text);
+
+        assert_eq!(rope, text.as_str());
+        assert_eq!(rope.validate(), Ok(()));
+        assert!(rope
+            .iter()
+            .all(|n| n.map_leaf().unwrap().value.len() <= MAX_LEAF_LEN));
+        assert!(Rope::par_from_str("").is_empty());
+    }
+
+    #[test]
+    fn par_find_all_test() {
+        let text = text();
+        let rope = Rope::par_from_str(&text);
+
+        for pattern in ["ääh\nline 1", "e", "aa", "line 4999 ", "of the"] {
+            assert_eq!(
+                rope.par_find_all(pattern),
+                find_overlapping(&text, pattern),
+                "{pattern}"
+            );
+        }
+
+        assert_eq!(Rope::from("aaaa").par_find_all("aa"), vec![0, 1, 2]);
+        assert!(has_border(b"abcab") && has_border(b"aa"));
+        assert!(!has_border(b"lazy") && !has_border(b"a") && !has_border(b"abc"));
+    }
+
+    #[test]
+    fn find_all_across_groups_test() {
+        let text = text();
+        let rope = Rope::par_from_str(&text);
+
+        for threads in [1, 2, 3, 8, rope.iter().count() + 1] {
+            for pattern in ["ääh\nline 1", "e", "of the"] {
+                assert_eq!(
+                    rope.find_all_on(pattern, Some(threads)),
+                    find_overlapping(&text, pattern),
+                    "{pattern} on {threads} threads"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn threads_test() {
+        assert_eq!(threads(0), 1);
+        assert_eq!(threads(MIN_THREAD_LEN - 1), 1);
+        assert!(threads(usize::MAX) >= 1);
+    }
+
+    #[test]
+    fn par_chunks_and_lines_test() {
+        let text = text();
+        let rope = Rope::par_from_str(&text);
+
+        let lens = rope.par_chunks().map(|chunk| chunk.len());
+
+        assert_eq!(lens.iter().sum::<usize>(), text.len());
+        assert_eq!(rope.par_line_count(), 5001);
+        assert_eq!(Rope::default().par_line_count(), 1);
+    }
+}
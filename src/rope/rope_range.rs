@@ -0,0 +1,293 @@
+use std::{
+    fmt::Display,
+    ops::{Bound, Range, RangeBounds},
+};
+
+use crate::error::Error;
+
+use super::{
+    rope::Rope,
+    rope_chunk::Chunk,
+    rope_node::{RopeNode, RopeNodeType},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RopeError {
+    /// `index` lies past the end of a rope of length `len`.
+    OutOfBounds { index: usize, len: usize },
+    /// `index` splits a multi-byte char.
+    NotCharBoundary(usize),
+    /// The range ends before it starts.
+    InvalidRange { start: usize, end: usize },
+    /// Two edits passed to [`Rope::try_apply_edits`] overlap.
+    OverlappingRanges {
+        first: Range<usize>,
+        second: Range<usize>,
+    },
+}
+
+impl Display for RopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RopeError::OutOfBounds { index, len } => {
+                write!(f, "index {index} is out of bounds for length {len}")
+            }
+            RopeError::NotCharBoundary(index) => write!(f, "index {index} is not a char boundary"),
+            RopeError::InvalidRange { start, end } => {
+                write!(f, "range start {start} is greater than its end {end}")
+            }
+            RopeError::OverlappingRanges { first, second } => {
+                write!(f, "ranges {first:?} and {second:?} overlap")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RopeError {}
+
+impl<T: Chunk> RopeNode<T> {
+    /// Whether the rope may be split at `index`, false past the end.
+    pub fn is_boundary(&self, index: usize) -> bool {
+        let mut node = self.0.as_ref();
+        let mut index = index;
+
+        loop {
+            match node {
+                RopeNodeType::Node(n) if index >= n.weight => {
+                    index -= n.weight;
+                    node = n.right.0.as_ref();
+                }
+                RopeNodeType::Node(n) => node = n.left.0.as_ref(),
+                RopeNodeType::Leaf(leaf) => return leaf.value.is_boundary(index),
+                RopeNodeType::None => return index == 0,
+            }
+        }
+    }
+
+    /// See [`Rope::check_range`].
+    pub fn check_range(&self, range: impl RangeBounds<usize>) -> Result<Range<usize>, Error> {
+        let len = self.measure();
+
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end.saturating_add(1),
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => len,
+        };
+
+        if start > end {
+            return Err(RopeError::InvalidRange { start, end }.into());
+        }
+
+        for index in [start, end] {
+            self.check_index(index, len)?;
+        }
+
+        Ok(start..end)
+    }
+
+    /// Resolves `len` bytes from `start` for the methods without a `try_`
+    /// prefix, panicking with the error [`RopeNode::check_range`] returns.
+    pub(crate) fn expect_range(&self, start: usize, len: usize) -> Range<usize> {
+        match self.check_range(start..start.saturating_add(len)) {
+            Ok(range) => range,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub(crate) fn check_index(&self, index: usize, len: usize) -> Result<(), RopeError> {
+        match index {
+            i if i > len => Err(RopeError::OutOfBounds { index, len }),
+            i if !self.is_boundary(i) => Err(RopeError::NotCharBoundary(index)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T: Chunk> Rope<T> {
+    /// Resolves `range` against the rope. Both ends have to be in bounds
+    /// and on a boundary.
+    pub fn check_range(&self, range: impl RangeBounds<usize>) -> Result<Range<usize>, Error> {
+        self.root.check_range(range)
+    }
+
+    /// Copy of `range` as a new rope sharing the untouched leafs.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Result<Rope<T>, Error> {
+        let range = self.check_range(range)?;
+
+        Ok(Rope::from_iter(
+            self.root.substring(range.start, range.len()).iter(),
+        ))
+    }
+
+    pub fn remove(&mut self, range: impl RangeBounds<usize>) -> Result<(), Error> {
+        let range = self.check_range(range)?;
+        self.delete(range.start, range.len());
+
+        Ok(())
+    }
+
+    pub fn replace(&mut self, range: impl RangeBounds<usize>, value: T) -> Result<(), Error> {
+        let range = self.check_range(range)?;
+        self.apply_edits(vec![(range, value)]);
+
+        Ok(())
+    }
+
+    pub fn try_insert(&mut self, index: usize, value: T) -> Result<(), Error> {
+        self.check_index(index, self.root.measure())?;
+        self.insert(index, value);
+
+        Ok(())
+    }
+
+    pub fn try_delete(&mut self, start: usize, len: usize) -> Result<(), Error> {
+        self.remove(start..start.saturating_add(len))
+    }
+
+    pub fn try_substring(&mut self, start: usize, len: usize) -> Result<(), Error> {
+        let range = self.check_range(start..start.saturating_add(len))?;
+        self.substring(range.start, range.len());
+
+        Ok(())
+    }
+
+    pub fn try_split_off(&mut self, index: usize) -> Result<Rope<T>, Error> {
+        self.check_index(index, self.root.measure())?;
+
+        Ok(self.split_off(index))
+    }
+
+    pub fn try_split(&self, index: usize) -> Result<(RopeNode<T>, RopeNode<T>), Error> {
+        self.check_index(index, self.root.measure())?;
+
+        Ok(self.split(index))
+    }
+
+    /// Like [`Rope::apply_edits`], but fails on invalid or overlapping
+    /// ranges instead of clamping them.
+    pub fn try_apply_edits(&mut self, edits: Vec<(Range<usize>, T)>) -> Result<(), Error> {
+        let mut ranges = edits
+            .iter()
+            .map(|(range, _)| self.check_range(range.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        ranges.sort_by_key(|range| range.start);
+
+        for pair in ranges.windows(2) {
+            if pair[1].start < pair[0].end {
+                return Err(RopeError::OverlappingRanges {
+                    first: pair[0].clone(),
+                    second: pair[1].clone(),
+                }
+                .into());
+            }
+        }
+
+        self.apply_edits(edits);
+
+        Ok(())
+    }
+
+    pub(crate) fn check_index(&self, index: usize, len: usize) -> Result<(), RopeError> {
+        self.root.check_index(index, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_test() {
+        let mut rope = Rope::from("hello ");
+        rope.append(String::from("wörld"));
+
+        assert_eq!(rope.slice(2..9).unwrap(), "llo wö");
+        assert_eq!(rope.slice(..5).unwrap(), "hello");
+        assert_eq!(rope.slice(6..).unwrap(), "wörld");
+        assert_eq!(rope.slice(..=0).unwrap(), "h");
+        assert_eq!(rope.slice(..).unwrap(), rope);
+        assert!(rope.slice(3..3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn range_errors_test() {
+        let mut rope = Rope::from("wörld");
+
+        assert_eq!(
+            rope.slice(2..3).unwrap_err(),
+            RopeError::NotCharBoundary(2).into()
+        );
+        assert_eq!(
+            rope.remove(4..10).unwrap_err(),
+            RopeError::OutOfBounds { index: 10, len: 6 }.into()
+        );
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 4..1;
+        assert_eq!(
+            rope.replace(reversed, String::new()).unwrap_err(),
+            RopeError::InvalidRange { start: 4, end: 1 }.into()
+        );
+        assert_eq!(rope, "wörld");
+    }
+
+    #[test]
+    fn remove_and_replace_test() {
+        let mut rope = Rope::from("hello wörld");
+
+        rope.remove(..6).unwrap();
+        assert_eq!(rope, "wörld");
+
+        rope.replace(1..3, String::from("o")).unwrap();
+        assert_eq!(rope, "world");
+
+        rope.replace(5.., String::from("!")).unwrap();
+        rope.remove(..).unwrap();
+        assert!(rope.is_empty());
+    }
+
+    #[test]
+    fn try_variants_test() {
+        let mut rope = Rope::from("hello wörld");
+
+        assert_eq!(
+            rope.try_insert(8, String::from("!")),
+            Err(RopeError::NotCharBoundary(8).into())
+        );
+        assert_eq!(
+            rope.try_delete(10, 5),
+            Err(RopeError::OutOfBounds { index: 15, len: 12 }.into())
+        );
+        assert_eq!(
+            rope.try_apply_edits(vec![(6..9, String::new()), (0..7, String::new())]),
+            Err(RopeError::OverlappingRanges {
+                first: 0..7,
+                second: 6..9
+            }
+            .into())
+        );
+        assert!(rope.try_split_off(13).is_err());
+        assert_eq!(
+            rope.try_split(8).unwrap_err(),
+            RopeError::NotCharBoundary(8).into()
+        );
+        assert_eq!(rope, "hello wörld");
+
+        rope.try_apply_edits(vec![(6..9, String::from("wo")), (0..1, String::from("H"))])
+            .unwrap();
+        rope.try_insert(10, String::from("!")).unwrap();
+        rope.try_substring(0, 7).unwrap();
+
+        let (left, right) = rope.try_split(1).unwrap();
+        assert_eq!(Rope::new(left), "H");
+        assert_eq!(Rope::new(right), "ello w");
+
+        assert_eq!(rope.try_split_off(5).unwrap(), " w");
+        assert_eq!(rope, "Hello");
+    }
+}
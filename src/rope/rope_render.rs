@@ -0,0 +1,162 @@
+use std::fmt::{Debug, Write};
+
+use super::{
+    rope::Rope,
+    rope_node::{RopeNode, RopeNodeType},
+};
+
+impl<T: Debug> RopeNode<T> {
+    /// Renders the tree with one node per line, e.g.
+    ///
+    /// ```text
+    /// Node(weight: 6)
+    /// ├── Leaf("hello ")
+    /// └── Leaf("world")
+    /// ```
+    pub fn to_ascii_tree(&self) -> String {
+        let mut out = String::new();
+        ascii_rec(&self.0, "", "", &mut out);
+
+        out
+    }
+
+    /// Renders the tree as a Graphviz digraph, inner nodes are labeled with
+    /// their weight and leafs with their content.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph rope {\n    node [fontname=\"monospace\"];\n");
+        dot_rec(&self.0, &mut 0, &mut out);
+        out.push_str("}\n");
+
+        out
+    }
+}
+
+impl<T: Debug> Rope<T> {
+    pub fn to_ascii_tree(&self) -> String {
+        self.root.to_ascii_tree()
+    }
+
+    pub fn to_dot(&self) -> String {
+        self.root.to_dot()
+    }
+}
+
+fn ascii_rec<T: Debug>(node: &RopeNodeType<T>, prefix: &str, child_prefix: &str, out: &mut String) {
+    match node {
+        RopeNodeType::Node(n) => {
+            _ = writeln!(out, "{prefix}Node(weight: {})", n.weight);
+
+            ascii_rec(
+                &n.left.0,
+                &format!("{child_prefix}├── "),
+                &format!("{child_prefix}│   "),
+                out,
+            );
+            ascii_rec(
+                &n.right.0,
+                &format!("{child_prefix}└── "),
+                &format!("{child_prefix}    "),
+                out,
+            );
+        }
+        RopeNodeType::Leaf(leaf) => _ = writeln!(out, "{prefix}Leaf({:?})", leaf.value),
+        RopeNodeType::None => _ = writeln!(out, "{prefix}None"),
+    }
+}
+
+/// Returns the id given to `node`.
+fn dot_rec<T: Debug>(node: &RopeNodeType<T>, next_id: &mut usize, out: &mut String) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+
+    match node {
+        RopeNodeType::Node(n) => {
+            _ = writeln!(out, "    n{id} [label=\"weight: {}\"];", n.weight);
+
+            for (child, side) in [(&n.left, "L"), (&n.right, "R")] {
+                let child_id = dot_rec(&child.0, next_id, out);
+                _ = writeln!(out, "    n{id} -> n{child_id} [label=\"{side}\"];");
+            }
+        }
+        RopeNodeType::Leaf(leaf) => {
+            let label = format!("{:?}", leaf.value)
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+
+            _ = writeln!(out, "    n{id} [shape=box, label=\"{label}\"];");
+        }
+        RopeNodeType::None => _ = writeln!(out, "    n{id} [shape=point];"),
+    }
+
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rope::rope_node::Leaf;
+    use std::sync::Arc;
+
+    fn rope(leafs: &[&str]) -> Rope {
+        Rope::from_iter(leafs.iter().map(|l| {
+            RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf {
+                value: String::from(*l),
+            })))
+        }))
+    }
+
+    #[test]
+    fn display_prints_text_test() {
+        let rope = rope(&["hello ", "world", "!"]);
+
+        assert_eq!(format!("{rope}"), "hello world!");
+        assert_eq!(format!("{}", Rope::<String>::default()), "");
+    }
+
+    #[test]
+    fn display_pads_whole_text_test() {
+        let rope = rope(&["hello ", "world", "!"]);
+
+        assert_eq!(format!("{rope:>15}"), "   hello world!");
+        assert_eq!(format!("{rope:-<14}"), "hello world!--");
+        assert_eq!(format!("{rope:.8}"), "hello wo");
+        assert_eq!(format!("{rope:^10.3}"), "   hel    ");
+    }
+
+    #[test]
+    fn ascii_tree_test() {
+        let mut rope = rope(&["hello ", "world", "!"]);
+        rope.insert(0, String::from("\""));
+
+        let expected = r#"Node(weight: 7)
+├── Node(weight: 1)
+│   ├── Leaf("\"")
+│   └── Leaf("hello ")
+└── Node(weight: 5)
+    ├── Leaf("world")
+    └── Leaf("!")
+"#;
+
+        assert_eq!(rope.to_ascii_tree(), expected);
+        assert_eq!(Rope::<String>::default().to_ascii_tree(), "None\n");
+    }
+
+    #[test]
+    fn dot_test() {
+        let rope = Rope::new(RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf {
+            value: String::from("say \"hi\""),
+        }))));
+
+        let expected = r#"digraph rope {
+    node [fontname="monospace"];
+    n0 [label="weight: 8"];
+    n1 [shape=box, label="\"say \\\"hi\\\"\""];
+    n0 -> n1 [label="L"];
+    n2 [shape=point];
+    n0 -> n2 [label="R"];
+}
+"#;
+
+        assert_eq!(rope.to_dot(), expected);
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fmt, sync::Arc};
+
+use serde::{
+    de::{SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use super::{
+    rope::Rope,
+    rope_node::{Leaf, RopeNode, RopeNodeType},
+};
+
+/// Ropes are written as a plain string. Use [`chunks`] to keep the leaf
+/// layout instead.
+impl Serialize for Rope {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value: String = self
+            .iter()
+            .filter_map(|n| n.map_leaf().map(|l| l.value.clone()))
+            .collect();
+
+        serializer.serialize_str(&value)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        match value.is_empty() {
+            true => Ok(Rope::from_iter(vec![])),
+            false => Ok(Rope::new(
+                Arc::new(RopeNodeType::Leaf(Leaf { value })).into(),
+            )),
+        }
+    }
+}
+
+/// Writes a rope as a sequence of its leaf strings, so that the chunk
+/// structure survives a round trip. Meant for `#[serde(with = "chunks")]`.
+pub mod chunks {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(rope: &Rope, serializer: S) -> Result<S::Ok, S::Error> {
+        let leafs: Vec<RopeNode> = rope.iter().collect();
+        let mut seq = serializer.serialize_seq(Some(leafs.len()))?;
+
+        for leaf in leafs.iter().filter_map(|n| n.map_leaf()) {
+            seq.serialize_element(&leaf.value)?;
+        }
+
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rope, D::Error> {
+        deserializer.deserialize_seq(ChunksVisitor)
+    }
+
+    struct ChunksVisitor;
+
+    impl<'de> Visitor<'de> for ChunksVisitor {
+        type Value = Rope;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a sequence of rope chunks")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut leafs = vec![];
+
+            while let Some(value) = seq.next_element::<String>()? {
+                leafs.push(RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf { value }))));
+            }
+
+            Ok(Rope::from_iter(leafs))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rope(leafs: &[&str]) -> Rope {
+        Rope::from_iter(leafs.iter().map(|l| {
+            RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf {
+                value: String::from(*l),
+            })))
+        }))
+    }
+
+    fn leafs(rope: &Rope) -> Vec<String> {
+        rope.iter()
+            .map(|n| n.map_leaf().unwrap().value.clone())
+            .collect()
+    }
+
+    #[test]
+    fn rope_string_round_trip_test() {
+        let original = rope(&["hello ", "world", "!"]);
+
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(json, r#""hello world!""#);
+
+        let restored: Rope = serde_json::from_str(&json).unwrap();
+        assert_eq!(leafs(&restored), vec!["hello world!"]);
+
+        let empty: Rope = serde_json::from_str("\"\"").unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn rope_chunks_round_trip_test() {
+        let original = rope(&["hello ", "world", "!"]);
+
+        let mut json = vec![];
+        chunks::serialize(&original, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        assert_eq!(json, br#"["hello ","world","!"]"#);
+
+        let restored =
+            chunks::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+        assert_eq!(leafs(&restored), vec!["hello ", "world", "!"]);
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::{self, Read, Write},
+    sync::Arc,
+};
+
+use super::{
+    rope::Rope,
+    rope_anchor::AnchorSet,
+    rope_node::{Leaf, Node, RopeNode, RopeNodeType},
+    rope_validate::{ValidationError, Violation, MAX_DEPTH},
+};
+
+pub const SNAPSHOT_MAGIC: &[u8; 4] = b"RSNP";
+pub const SNAPSHOT_VERSION: u8 = 1;
+
+const TAG_NONE: u8 = 0;
+const TAG_LEAF: u8 = 1;
+const TAG_NODE: u8 = 2;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u8),
+    InvalidTag(u8),
+    InvalidNodeId(usize),
+    InvalidUtf8,
+    MissingRoot,
+    /// The decoded trees break the rope invariants, e.g. a weight that
+    /// doesn't match the length of the left subtree.
+    Invalid(ValidationError),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "snapshot io error: {err}"),
+            SnapshotError::BadMagic => write!(f, "not a rope snapshot"),
+            SnapshotError::UnsupportedVersion(v) => {
+                write!(f, "unsupported snapshot version {v}")
+            }
+            SnapshotError::InvalidTag(tag) => write!(f, "invalid node tag {tag}"),
+            SnapshotError::InvalidNodeId(id) => write!(f, "reference to unknown node {id}"),
+            SnapshotError::InvalidUtf8 => write!(f, "leaf is not valid utf-8"),
+            SnapshotError::MissingRoot => write!(f, "snapshot contains no rope"),
+            SnapshotError::Invalid(err) => write!(f, "snapshot contains an {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(value: io::Error) -> Self {
+        SnapshotError::Io(value)
+    }
+}
+
+/// Writes ropes into a versioned binary snapshot.
+///
+/// Layout: magic, version, node count, nodes, root count, root ids. Nodes
+/// are written children first and every distinct `Arc<RopeNodeType>` only
+/// once, so ropes sharing subtrees (e.g. an undo history) stay compact.
+/// Integers are LEB128 encoded.
+pub fn write_snapshots<W: Write>(mut writer: W, ropes: &[&Rope]) -> io::Result<()> {
+    let mut ids = HashMap::new();
+    let mut body = vec![];
+
+    let roots: Vec<usize> = ropes
+        .iter()
+        .map(|rope| write_node(&rope.root.0, &mut ids, &mut body))
+        .collect();
+
+    let mut out = SNAPSHOT_MAGIC.to_vec();
+    out.push(SNAPSHOT_VERSION);
+    push_varint(&mut out, ids.len());
+    out.extend_from_slice(&body);
+    push_varint(&mut out, roots.len());
+
+    for root in roots {
+        push_varint(&mut out, root);
+    }
+
+    writer.write_all(&out)
+}
+
+/// Restores ropes written by [`write_snapshots`] with their subtree sharing.
+///
+/// Every node is checked like [`RopeNode::validate_structure`] does when it
+/// is decoded, so shared subtrees are checked once and a hostile snapshot
+/// can't hand out trees whose weights send lookups out of bounds.
+pub fn read_snapshots<R: Read>(mut reader: R) -> Result<Vec<Rope>, SnapshotError> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+
+    if &magic != SNAPSHOT_MAGIC {
+        return Err(SnapshotError::BadMagic);
+    }
+
+    let mut version = [0; 1];
+    reader.read_exact(&mut version)?;
+
+    if version[0] != SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version[0]));
+    }
+
+    let nodes_count = read_varint(&mut reader)?;
+    let mut nodes: Vec<Arc<RopeNodeType>> = vec![];
+    // length and depth of every decoded node, by id
+    let mut measures: Vec<(usize, usize)> = vec![];
+    let mut violations = vec![];
+
+    for id in 0..nodes_count {
+        let mut tag = [0; 1];
+        reader.read_exact(&mut tag)?;
+        let path = || format!("node {id}");
+
+        let (node, len, depth) = match tag[0] {
+            TAG_NONE => (RopeNodeType::None, 0, 0),
+            TAG_LEAF => {
+                let len = read_varint(&mut reader)?;
+                let mut value = vec![];
+
+                if reader.by_ref().take(len as u64).read_to_end(&mut value)? != len {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+                }
+
+                if value.is_empty() {
+                    violations.push(Violation::EmptyLeaf { path: path() });
+                }
+
+                let leaf = Leaf {
+                    value: String::from_utf8(value).map_err(|_| SnapshotError::InvalidUtf8)?,
+                };
+
+                (RopeNodeType::Leaf(leaf), len, 0)
+            }
+            TAG_NODE => {
+                let weight = read_varint(&mut reader)?;
+                let left_id = read_varint(&mut reader)?;
+                let right_id = read_varint(&mut reader)?;
+                let left = node_by_id(&nodes, left_id)?;
+                let right = node_by_id(&nodes, right_id)?;
+                let (left_len, left_depth) = measures[left_id];
+                let (right_len, right_depth) = measures[right_id];
+
+                match (left.0.as_ref(), right.0.as_ref()) {
+                    (RopeNodeType::None, RopeNodeType::None) => {
+                        violations.push(Violation::EmptyNode { path: path() })
+                    }
+                    (RopeNodeType::None, _) => {
+                        violations.push(Violation::MissingLeft { path: path() })
+                    }
+                    _ => (),
+                }
+
+                if left_len != weight {
+                    violations.push(Violation::WrongWeight {
+                        path: path(),
+                        expected: left_len,
+                        actual: weight,
+                    });
+                }
+
+                // deeper trees would overflow the stack of recursive walks,
+                // so stop before building on them
+                let depth = left_depth.max(right_depth) + 1;
+
+                if depth > MAX_DEPTH {
+                    violations.push(Violation::DepthExceeded {
+                        depth,
+                        max: MAX_DEPTH,
+                    });
+
+                    return Err(SnapshotError::Invalid(ValidationError { violations }));
+                }
+
+                let len = left_len.checked_add(right_len).ok_or_else(|| {
+                    SnapshotError::Io(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "rope length overflow",
+                    ))
+                })?;
+
+                let node = RopeNodeType::Node(Node {
+                    left,
+                    right,
+                    weight,
+                });
+
+                (node, len, depth)
+            }
+            tag => return Err(SnapshotError::InvalidTag(tag)),
+        };
+
+        nodes.push(Arc::new(node));
+        measures.push((len, depth));
+    }
+
+    if !violations.is_empty() {
+        return Err(SnapshotError::Invalid(ValidationError { violations }));
+    }
+
+    let roots_count = read_varint(&mut reader)?;
+
+    (0..roots_count)
+        .map(|_| {
+            Ok(Rope {
+                root: node_by_id(&nodes, read_varint(&mut reader)?)?,
+                anchors: AnchorSet::default(),
+            })
+        })
+        .collect()
+}
+
+impl Rope {
+    pub fn write_snapshot<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_snapshots(writer, &[self])
+    }
+
+    /// Reads the first rope of a snapshot.
+    pub fn read_snapshot<R: Read>(reader: R) -> Result<Rope, SnapshotError> {
+        read_snapshots(reader)?
+            .into_iter()
+            .next()
+            .ok_or(SnapshotError::MissingRoot)
+    }
+}
+
+fn write_node(
+    node: &Arc<RopeNodeType>,
+    ids: &mut HashMap<*const RopeNodeType, usize>,
+    out: &mut Vec<u8>,
+) -> usize {
+    if let Some(id) = ids.get(&Arc::as_ptr(node)) {
+        return *id;
+    }
+
+    match node.as_ref() {
+        RopeNodeType::None => out.push(TAG_NONE),
+        RopeNodeType::Leaf(leaf) => {
+            out.push(TAG_LEAF);
+            push_varint(out, leaf.value.len());
+            out.extend_from_slice(leaf.value.as_bytes());
+        }
+        RopeNodeType::Node(n) => {
+            let left = write_node(&n.left.0, ids, out);
+            let right = write_node(&n.right.0, ids, out);
+
+            out.push(TAG_NODE);
+
+            for value in [n.weight, left, right] {
+                push_varint(out, value);
+            }
+        }
+    }
+
+    let id = ids.len();
+    ids.insert(Arc::as_ptr(node), id);
+
+    id
+}
+
+fn node_by_id(nodes: &[Arc<RopeNodeType>], id: usize) -> Result<RopeNode, SnapshotError> {
+    nodes
+        .get(id)
+        .map(|node| Arc::clone(node).into())
+        .ok_or(SnapshotError::InvalidNodeId(id))
+}
+
+fn push_varint(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        match value {
+            0 => return out.push(byte),
+            _ => out.push(byte | 0x80),
+        }
+    }
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> Result<usize, SnapshotError> {
+    let mut value = 0;
+    let mut shift = 0;
+
+    loop {
+        let mut byte = [0; 1];
+        reader.read_exact(&mut byte)?;
+
+        if shift >= usize::BITS {
+            return Err(SnapshotError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "varint overflow",
+            )));
+        }
+
+        value |= ((byte[0] & 0x7f) as usize) << shift;
+        shift += 7;
+
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(value: &str) -> RopeNode {
+        Arc::new(RopeNodeType::Leaf(Leaf {
+            value: String::from(value),
+        }))
+        .into()
+    }
+
+    #[test]
+    fn snapshot_round_trip_test() {
+        let rope = Rope::from_iter(vec![leaf("hello "), leaf("wörld"), leaf("!")]);
+
+        let mut bytes = vec![];
+        rope.write_snapshot(&mut bytes).unwrap();
+
+        let restored = Rope::read_snapshot(bytes.as_slice()).unwrap();
+
+        assert_eq!(format!("{}", restored.root), format!("{}", rope.root));
+    }
+
+    #[test]
+    fn snapshot_sharing_test() {
+        let mut first = Rope::from_iter(vec![leaf("hello "), leaf("world"), leaf("!")]);
+        let second = Rope::new(Arc::clone(&first.root.0).into());
+        first.insert(0, String::from(">> "));
+
+        let mut bytes = vec![];
+        write_snapshots(&mut bytes, &[&first, &second]).unwrap();
+
+        let restored = read_snapshots(bytes.as_slice()).unwrap();
+        assert_eq!(restored.len(), 2);
+
+        let first_leafs: Vec<RopeNode> = restored[0].iter().collect();
+        let second_leafs: Vec<RopeNode> = restored[1].iter().collect();
+
+        assert_eq!(first_leafs.len(), 4);
+        assert_eq!(second_leafs.len(), 3);
+
+        for (a, b) in first_leafs[1..].iter().zip(second_leafs.iter()) {
+            assert!(Arc::ptr_eq(&a.0, &b.0));
+        }
+
+        assert_eq!(bytes.iter().filter(|b| **b == b'w').count(), 1);
+    }
+
+    #[test]
+    fn snapshot_invalid_test() {
+        assert!(matches!(
+            read_snapshots(&b"NOPE"[..]),
+            Err(SnapshotError::BadMagic)
+        ));
+        assert!(matches!(
+            read_snapshots(&b"RSNP\x07"[..]),
+            Err(SnapshotError::UnsupportedVersion(7))
+        ));
+        assert!(matches!(
+            read_snapshots(&b"RSNP\x01\x01\x02\x00\x05\x05"[..]),
+            Err(SnapshotError::InvalidNodeId(5))
+        ));
+    }
+
+    #[test]
+    fn snapshot_tampered_weight_test() {
+        // leafs "ab" and "c" under a node of weight 2
+        let mut bytes = b"RSNP\x01\x03\x01\x02ab\x01\x01c\x02\x02\x00\x01\x01\x02".to_vec();
+
+        let rope = Rope::read_snapshot(bytes.as_slice()).unwrap();
+        assert_eq!(rope.get_char(2), Some('c'));
+
+        bytes[14] = 9;
+
+        match Rope::read_snapshot(bytes.as_slice()) {
+            Err(SnapshotError::Invalid(err)) => assert_eq!(
+                err.violations,
+                [Violation::WrongWeight {
+                    path: String::from("node 2"),
+                    expected: 2,
+                    actual: 9,
+                }]
+            ),
+            result => panic!("expected an invalid snapshot, got {result:?}"),
+        }
+    }
+}
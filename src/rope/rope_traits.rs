@@ -0,0 +1,232 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use super::{
+    rope::Rope,
+    rope_chunk::Chunk,
+    rope_node::{Leaf, RopeNode, RopeNodeType},
+};
+
+impl<T> Clone for RopeNode<T> {
+    fn clone(&self) -> Self {
+        RopeNode(Arc::clone(&self.0))
+    }
+}
+
+/// Shares the tree with the original, anchors are copied.
+impl<T> Clone for Rope<T> {
+    fn clone(&self) -> Self {
+        Rope {
+            root: self.root.clone(),
+            anchors: self.anchors.clone(),
+        }
+    }
+}
+
+impl<T: Chunk> Default for Rope<T> {
+    fn default() -> Self {
+        Rope::from_iter(vec![])
+    }
+}
+
+/// Flattens leafs into their items.
+fn items<T: Chunk>(leafs: &[RopeNode<T>]) -> impl Iterator<Item = &T::Item> {
+    leafs
+        .iter()
+        .filter_map(|n| n.map_leaf())
+        .flat_map(|leaf| (0..leaf.value.len()).filter_map(|i| leaf.value.get(i)))
+}
+
+fn leafs<T: Chunk>(rope: &Rope<T>) -> Vec<RopeNode<T>> {
+    rope.iter().collect()
+}
+
+/// Ropes compare by content, the chunk layout doesn't matter.
+impl<T: Chunk> PartialEq for Rope<T>
+where
+    T::Item: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.root.measure() == other.root.measure() && items(&leafs(self)).eq(items(&leafs(other)))
+    }
+}
+
+impl<T: Chunk> Eq for Rope<T> where T::Item: Eq {}
+
+impl<T: Chunk> PartialOrd for Rope<T>
+where
+    T::Item: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Chunk> Ord for Rope<T>
+where
+    T::Item: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        items(&leafs(self)).cmp(items(&leafs(other)))
+    }
+}
+
+impl<T: Chunk> Hash for Rope<T>
+where
+    T::Item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.root.measure());
+        items(&leafs(self)).for_each(|item| item.hash(state));
+    }
+}
+
+impl PartialEq<str> for Rope {
+    fn eq(&self, other: &str) -> bool {
+        self.root.measure() == other.len() && items(&leafs(self)).eq(other.as_bytes())
+    }
+}
+
+impl PartialEq<&str> for Rope {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<String> for Rope {
+    fn eq(&self, other: &String) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Rope> for str {
+    fn eq(&self, other: &Rope) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Rope> for &str {
+    fn eq(&self, other: &Rope) -> bool {
+        other == *self
+    }
+}
+
+impl PartialEq<Rope> for String {
+    fn eq(&self, other: &Rope) -> bool {
+        other == self
+    }
+}
+
+impl Extend<char> for Rope {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        self.append(iter.into_iter().collect());
+    }
+}
+
+impl<'a> Extend<&'a str> for Rope {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        self.append(iter.into_iter().collect());
+    }
+}
+
+impl fmt::Write for Rope {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.append(s.to_string());
+
+        Ok(())
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(value: &str) -> Self {
+        match value.is_empty() {
+            true => Rope::default(),
+            false => Rope::new(
+                Arc::new(RopeNodeType::Leaf(Leaf {
+                    value: value.to_string(),
+                }))
+                .into(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::hash_map::DefaultHasher, collections::HashMap, fmt::Write};
+
+    fn rope(leafs: &[&str]) -> Rope {
+        Rope::from_iter(leafs.iter().map(|l| {
+            RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf {
+                value: String::from(*l),
+            })))
+        }))
+    }
+
+    fn hash(rope: &Rope) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        rope.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    #[test]
+    fn eq_independent_of_layout_test() {
+        let first = rope(&["hello ", "world"]);
+        let second = rope(&["hel", "lo w", "orld"]);
+
+        assert_eq!(first, second);
+        assert_eq!(hash(&first), hash(&second));
+        assert_eq!(first, "hello world");
+        assert_eq!(String::from("hello world"), second);
+        assert_ne!(first, rope(&["hello ", "world!"]));
+        assert_eq!(Rope::default(), "");
+    }
+
+    #[test]
+    fn ord_test() {
+        let mut ropes = vec![rope(&["b", "c"]), rope(&["ab", "d"]), rope(&["a", "b"])];
+        ropes.sort();
+
+        assert_eq!(ropes, vec!["ab", "abd", "bc"]);
+        assert!(rope(&["ab"]) < rope(&["a", "c"]));
+    }
+
+    #[test]
+    fn map_key_test() {
+        let mut map = HashMap::new();
+        map.insert(rope(&["key ", "one"]), 1);
+
+        assert_eq!(map.get(&rope(&["key", " one"])), Some(&1));
+        assert_eq!(map.get(&rope(&["key one!"])), None);
+    }
+
+    #[test]
+    fn extend_and_write_test() {
+        let mut rope = Rope::from("hello");
+        let copy = rope.clone();
+
+        rope.extend([",", " "]);
+        rope.extend("wor".chars());
+
+        let end = "ld!";
+        write!(rope, "{end}").unwrap();
+
+        assert_eq!(rope, "hello, world!");
+        assert_eq!(copy, "hello");
+
+        let mut log = Rope::from("");
+
+        for i in 0..10_000 {
+            write!(log, "{i},").unwrap();
+        }
+
+        assert_eq!(log.len(), 48_890);
+        assert_eq!(log.validate_structure(), Ok(()));
+    }
+}
@@ -0,0 +1,397 @@
+use std::{cmp, fmt::Display};
+
+use crate::helpers::fibonacci_seq::get_fibonacci_number;
+
+use super::{
+    rope::Rope,
+    rope_chunk::Chunk,
+    rope_node::{RopeNode, RopeNodeType},
+};
+
+/// Deepest tree [`RopeNode::validate`] accepts. A balanced rope of this
+/// depth would need more leafs than fit in memory.
+pub const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// `weight` of a node differs from the length of its left subtree.
+    WrongWeight {
+        path: String,
+        expected: usize,
+        actual: usize,
+    },
+    /// A node with both children `None`, it should be `None` itself.
+    EmptyNode {
+        path: String,
+    },
+    /// A node with only a right child, which should be its left one.
+    MissingLeft {
+        path: String,
+    },
+    EmptyLeaf {
+        path: String,
+    },
+    /// The walk stops one level below `max`, so `depth` is `max + 1` however
+    /// deep the tree goes.
+    DepthExceeded {
+        depth: usize,
+        max: usize,
+    },
+    /// Fewer leafs than the Fibonacci criterion asks for at this depth.
+    /// Nodes with a single child don't count as a level.
+    Unbalanced {
+        depth: usize,
+        leafs: usize,
+        min_leafs: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub violations: Vec<Violation>,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::WrongWeight {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{path}: weight is {actual}, left subtree length is {expected}"
+            ),
+            Violation::EmptyNode { path } => write!(f, "{path}: node has no children"),
+            Violation::MissingLeft { path } => write!(f, "{path}: node has only a right child"),
+            Violation::EmptyLeaf { path } => write!(f, "{path}: leaf is empty"),
+            Violation::DepthExceeded { depth, max } => {
+                write!(f, "depth {depth} exceeds maximum of {max}")
+            }
+            Violation::Unbalanced {
+                depth,
+                leafs,
+                min_leafs,
+            } => write!(
+                f,
+                "depth {depth} needs at least {min_leafs} leafs, tree has {leafs}"
+            ),
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid rope:")?;
+
+        for violation in &self.violations {
+            write!(f, "\n  {violation}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl<T: Chunk> RopeNode<T> {
+    /// Checks the structure of the tree, see
+    /// [`RopeNode::validate_structure`], and that it is balanced by the
+    /// Fibonacci criterion [`RopeNode::rebalance`] restores. Nodes with a
+    /// single child, like the root [`Rope::new`] puts above a leaf, don't
+    /// add to the depth.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = self.structure_violations();
+
+        if violations.is_empty() {
+            let depth = branching_depth(&self.0);
+            let leafs = self.iter().count();
+            let min_leafs = get_fibonacci_number(depth + 2);
+
+            if leafs > 0 && leafs < min_leafs {
+                violations.push(Violation::Unbalanced {
+                    depth,
+                    leafs,
+                    min_leafs,
+                });
+            }
+        }
+
+        into_result(violations)
+    }
+
+    /// Checks the invariants every tree has to keep, balanced or not, and
+    /// lists every broken one: weights matching the left subtree, no
+    /// childless nodes or nodes with only a right child, no empty leafs and
+    /// a depth of at most [`MAX_DEPTH`].
+    pub fn validate_structure(&self) -> Result<(), ValidationError> {
+        into_result(self.structure_violations())
+    }
+
+    fn structure_violations(&self) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        match depth_within(&self.0, MAX_DEPTH) {
+            Some(_) => _ = validate_rec(&self.0, String::from("root"), &mut violations),
+            None => violations.push(Violation::DepthExceeded {
+                depth: MAX_DEPTH + 1,
+                max: MAX_DEPTH,
+            }),
+        }
+
+        violations
+    }
+}
+
+impl<T: Chunk> Rope<T> {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.root.validate()
+    }
+
+    pub fn validate_structure(&self) -> Result<(), ValidationError> {
+        self.root.validate_structure()
+    }
+
+    /// Called after every mutating operation. With the `debug-validate`
+    /// feature it panics on a broken tree in debug builds, otherwise it
+    /// compiles to nothing, as the check walks the whole tree. Balance
+    /// isn't checked, edits like `substring` leave that to the next insert.
+    pub(crate) fn debug_validate(&self) {
+        #[cfg(all(debug_assertions, feature = "debug-validate"))]
+        if let Err(err) = self.validate_structure() {
+            panic!("{err}");
+        }
+    }
+}
+
+fn into_result(violations: Vec<Violation>) -> Result<(), ValidationError> {
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(ValidationError { violations }),
+    }
+}
+
+/// Depth of the tree, `None` once it goes deeper than `max`. Stops there
+/// instead of walking on, so trees deep enough to overflow the stack are
+/// still reported.
+fn depth_within<T>(node: &RopeNodeType<T>, max: usize) -> Option<usize> {
+    match node {
+        RopeNodeType::Node(node) => {
+            let max = max.checked_sub(1)?;
+            let left = depth_within(&node.left.0, max)?;
+            let right = depth_within(&node.right.0, max)?;
+
+            Some(cmp::max(left, right) + 1)
+        }
+        RopeNodeType::Leaf(_) | RopeNodeType::None => Some(0),
+    }
+}
+
+/// Depth counting only the nodes with two children.
+fn branching_depth<T>(node: &RopeNodeType<T>) -> usize {
+    match node {
+        RopeNodeType::Node(node) => {
+            let left = branching_depth(&node.left.0);
+            let right = branching_depth(&node.right.0);
+
+            match (node.left.0.as_ref(), node.right.0.as_ref()) {
+                (RopeNodeType::None, _) | (_, RopeNodeType::None) => cmp::max(left, right),
+                _ => cmp::max(left, right) + 1,
+            }
+        }
+        RopeNodeType::Leaf(_) | RopeNodeType::None => 0,
+    }
+}
+
+/// Returns the length of the subtree.
+fn validate_rec<T: Chunk>(
+    node: &RopeNodeType<T>,
+    path: String,
+    violations: &mut Vec<Violation>,
+) -> usize {
+    match node {
+        RopeNodeType::Node(node) => {
+            match (node.left.0.as_ref(), node.right.0.as_ref()) {
+                (RopeNodeType::None, RopeNodeType::None) => {
+                    violations.push(Violation::EmptyNode { path: path.clone() })
+                }
+                (RopeNodeType::None, _) => {
+                    violations.push(Violation::MissingLeft { path: path.clone() })
+                }
+                _ => (),
+            }
+
+            let left = validate_rec(&node.left.0, format!("{path}.left"), violations);
+            let right = validate_rec(&node.right.0, format!("{path}.right"), violations);
+
+            if left != node.weight {
+                violations.push(Violation::WrongWeight {
+                    path,
+                    expected: left,
+                    actual: node.weight,
+                });
+            }
+
+            left + right
+        }
+        RopeNodeType::Leaf(leaf) => {
+            if leaf.value.is_empty() {
+                violations.push(Violation::EmptyLeaf { path });
+            }
+
+            leaf.value.len()
+        }
+        RopeNodeType::None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rope::rope_node::{Leaf, Node};
+    use std::sync::Arc;
+
+    fn leaf(value: &str) -> RopeNode {
+        Arc::new(RopeNodeType::Leaf(Leaf {
+            value: String::from(value),
+        }))
+        .into()
+    }
+
+    fn node(left: RopeNode, right: RopeNode, weight: usize) -> RopeNode {
+        Arc::new(RopeNodeType::Node(Node {
+            left,
+            right,
+            weight,
+        }))
+        .into()
+    }
+
+    fn none() -> RopeNode {
+        Arc::new(RopeNodeType::None).into()
+    }
+
+    #[test]
+    fn validate_valid_test() {
+        let root = node(node(leaf("hello "), leaf("world"), 6), leaf("!"), 11);
+
+        assert_eq!(root.validate(), Ok(()));
+        assert_eq!(none().validate(), Ok(()));
+        assert_eq!(Rope::new(leaf("hello")).validate(), Ok(()));
+
+        // leaves "a" on the left and three leafs on the right
+        let mut rope = Rope::from_iter(["a", "b", "c", "d", "e", "f", "g", "h"].map(leaf));
+        rope.delete(1, 4);
+
+        assert_eq!(rope.to_string(), "afgh");
+        assert_eq!(rope.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_violations_test() {
+        let root = node(
+            node(leaf("hello "), leaf(""), 5),
+            node(none(), none(), 0),
+            6,
+        );
+
+        assert_eq!(
+            root.validate(),
+            Err(ValidationError {
+                violations: vec![
+                    Violation::EmptyLeaf {
+                        path: String::from("root.left.right")
+                    },
+                    Violation::WrongWeight {
+                        path: String::from("root.left"),
+                        expected: 6,
+                        actual: 5
+                    },
+                    Violation::EmptyNode {
+                        path: String::from("root.right")
+                    },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn validate_depth_test() {
+        let mut root = leaf("a");
+
+        for _ in 0..MAX_DEPTH + 1 {
+            root = node(root, none(), 1);
+        }
+
+        assert_eq!(
+            root.validate(),
+            Err(ValidationError {
+                violations: vec![Violation::DepthExceeded {
+                    depth: MAX_DEPTH + 1,
+                    max: MAX_DEPTH
+                }]
+            })
+        );
+
+        for _ in 0..1_000_000 {
+            root = node(root, none(), 1);
+        }
+
+        assert!(matches!(
+            root.validate_structure().unwrap_err().violations[..],
+            [Violation::DepthExceeded { .. }]
+        ));
+        // dropping the nodes recurses as deep as the tree goes
+        std::mem::forget(root);
+    }
+
+    #[test]
+    fn validate_missing_left_test() {
+        let root = node(leaf("a"), node(none(), leaf("b"), 0), 1);
+
+        assert_eq!(
+            root.validate_structure(),
+            Err(ValidationError {
+                violations: vec![Violation::MissingLeft {
+                    path: String::from("root.right")
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn validate_unbalanced_test() {
+        // shallow, but four leafs are too few for depth 3
+        let root = node(
+            node(node(leaf("a"), leaf("b"), 1), leaf("c"), 2),
+            leaf("d"),
+            3,
+        );
+
+        assert_eq!(root.validate_structure(), Ok(()));
+        assert_eq!(
+            root.validate(),
+            Err(ValidationError {
+                violations: vec![Violation::Unbalanced {
+                    depth: 3,
+                    leafs: 4,
+                    min_leafs: 5
+                }]
+            })
+        );
+        assert_eq!(root.rebalance().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_after_edits_test() {
+        let mut rope = Rope::new(leaf("hello world"));
+
+        rope.insert(5, String::from(","));
+        rope.insert(0, String::new());
+        rope.delete(0, 3);
+        rope.delete(0, rope.len());
+        rope.insert(0, String::from("again"));
+
+        assert_eq!(rope.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,517 @@
+use std::{ops::Range, sync::Arc};
+
+use super::{rope::Rope, rope_node::RopeNodeType};
+
+const SENTENCE_TERMINATORS: [char; 3] = ['.', '!', '?'];
+const SENTENCE_CLOSERS: [char; 5] = [')', '"', '\'', '”', '’'];
+
+/// Chars that make up a word next to the alphanumeric ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordChars {
+    extra: Vec<char>,
+}
+
+impl Default for WordChars {
+    /// Alphanumerics and `_`, as in most C-like languages.
+    fn default() -> Self {
+        WordChars::new("_")
+    }
+}
+
+impl WordChars {
+    /// Alphanumerics plus every char of `extra`.
+    pub fn new(extra: &str) -> Self {
+        WordChars {
+            extra: extra.chars().collect(),
+        }
+    }
+
+    /// Identifiers like `kebab-case` and `valid?`.
+    pub fn lisp() -> Self {
+        WordChars::new("_-?!*<>=+/")
+    }
+
+    /// Identifiers like `font-size` and `--main-color`.
+    pub fn css() -> Self {
+        WordChars::new("_-")
+    }
+
+    /// Identifiers like `$name` and `$1`.
+    pub fn shell() -> Self {
+        WordChars::new("_$")
+    }
+
+    pub fn is_word(&self, c: char) -> bool {
+        c.is_alphanumeric() || self.extra.contains(&c)
+    }
+
+    fn class(&self, c: char) -> CharClass {
+        match c {
+            c if c.is_whitespace() => CharClass::Space,
+            c if self.is_word(c) => CharClass::Word,
+            _ => CharClass::Punctuation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Space,
+    Punctuation,
+}
+
+impl Rope {
+    /// Next offset after `offset` where a run of word chars, whitespace or
+    /// punctuation ends, the length of the rope at the end.
+    pub fn next_word_boundary(&self, offset: usize) -> usize {
+        self.next_word_boundary_with(offset, &WordChars::default())
+    }
+
+    pub fn next_word_boundary_with(&self, offset: usize, word_chars: &WordChars) -> usize {
+        let mut chars = CharsFrom::new(self, offset);
+
+        let Some((_, first)) = chars.next() else {
+            return self.len();
+        };
+
+        let class = word_chars.class(first);
+
+        chars
+            .find(|(_, c)| word_chars.class(*c) != class)
+            .map_or(self.len(), |(pos, _)| pos)
+    }
+
+    /// Previous offset before `offset` where a run of word chars, whitespace
+    /// or punctuation starts, 0 at the start.
+    pub fn prev_word_boundary(&self, offset: usize) -> usize {
+        self.prev_word_boundary_with(offset, &WordChars::default())
+    }
+
+    pub fn prev_word_boundary_with(&self, offset: usize, word_chars: &WordChars) -> usize {
+        let mut chars = CharsBefore::new(self, offset);
+
+        let Some((_, last)) = chars.next() else {
+            return 0;
+        };
+
+        let class = word_chars.class(last);
+
+        chars
+            .find(|(_, c)| word_chars.class(*c) != class)
+            .map_or(0, |(pos, c)| pos + c.len_utf8())
+    }
+
+    /// Range of the word under `offset`, or the one ending right before it.
+    pub fn word_at(&self, offset: usize) -> Option<Range<usize>> {
+        self.word_at_with(offset, &WordChars::default())
+    }
+
+    pub fn word_at_with(&self, offset: usize, word_chars: &WordChars) -> Option<Range<usize>> {
+        let (pos, c) = CharsFrom::new(self, offset)
+            .next()
+            .filter(|(_, c)| word_chars.is_word(*c))
+            .or_else(|| {
+                CharsBefore::new(self, offset)
+                    .next()
+                    .filter(|(_, c)| word_chars.is_word(*c))
+            })?;
+
+        let start = CharsBefore::new(self, pos)
+            .take_while(|(_, c)| word_chars.is_word(*c))
+            .last()
+            .map_or(pos, |(pos, _)| pos);
+
+        let end = CharsFrom::new(self, pos + c.len_utf8())
+            .find(|(_, c)| !word_chars.is_word(*c))
+            .map_or(self.len(), |(pos, _)| pos);
+
+        Some(start..end)
+    }
+
+    /// Start of the next sentence after `offset`. A sentence ends with `.`,
+    /// `!` or `?`, optionally followed by closing quotes or brackets, and
+    /// whitespace.
+    pub fn next_sentence_boundary(&self, offset: usize) -> usize {
+        let mut terminated = false;
+        let mut gap = false;
+
+        for (pos, c) in CharsFrom::new(self, offset) {
+            match c {
+                c if gap && !c.is_whitespace() => return pos,
+                c if terminated && c.is_whitespace() => gap = true,
+                c if SENTENCE_TERMINATORS.contains(&c) => terminated = true,
+                c if terminated && SENTENCE_CLOSERS.contains(&c) => (),
+                _ => terminated = false,
+            }
+        }
+
+        self.len()
+    }
+
+    /// Start of the sentence before `offset`, 0 at the start.
+    pub fn prev_sentence_boundary(&self, offset: usize) -> usize {
+        let mut candidate = None;
+        let mut gap = false;
+        let mut closers = false;
+
+        for (pos, c) in CharsBefore::new(self, offset) {
+            match c {
+                c if c.is_whitespace() => {
+                    gap = candidate.is_some() && !closers;
+                }
+                c if (gap || closers) && SENTENCE_TERMINATORS.contains(&c) => {
+                    if let Some(start) = candidate {
+                        return start;
+                    }
+                }
+                c if gap && SENTENCE_CLOSERS.contains(&c) => {
+                    gap = false;
+                    closers = true;
+                }
+                _ => {
+                    candidate = Some(pos);
+                    gap = false;
+                    closers = false;
+                }
+            }
+        }
+
+        0
+    }
+
+    /// Start of the next paragraph after `offset`, i.e. of the next line
+    /// that isn't blank and follows a blank one.
+    pub fn next_paragraph_boundary(&self, offset: usize) -> usize {
+        let mut line_start = offset;
+        let mut line_blank = CharsBefore::new(self, offset)
+            .next()
+            .is_none_or(|(_, c)| c == '\n');
+        let mut blank_seen = false;
+
+        for (pos, c) in CharsFrom::new(self, offset) {
+            match c {
+                '\n' => {
+                    blank_seen |= line_blank;
+                    line_start = pos + 1;
+                    line_blank = true;
+                }
+                c if c.is_whitespace() => (),
+                _ if blank_seen && line_start > offset => return line_start,
+                _ => line_blank = false,
+            }
+        }
+
+        self.len()
+    }
+
+    /// Start of the paragraph before `offset`, 0 at the start.
+    pub fn prev_paragraph_boundary(&self, offset: usize) -> usize {
+        let mut line_blank = true;
+        let mut later_start = None;
+
+        for (pos, c) in CharsBefore::new(self, offset) {
+            match c {
+                '\n' => {
+                    if let (true, Some(start)) = (line_blank, later_start) {
+                        return start;
+                    }
+
+                    let start = pos + 1;
+                    later_start = (start < offset && !line_blank).then_some(start);
+                    line_blank = true;
+                }
+                c if c.is_whitespace() => (),
+                _ => line_blank = false,
+            }
+        }
+
+        match (line_blank, later_start) {
+            (true, Some(start)) => start,
+            _ => 0,
+        }
+    }
+}
+
+/// Path from the root down to one leaf, to step to the neighbouring leafs
+/// in either direction without walking the rope from its start.
+struct WordLeafCursor {
+    /// Ancestors of the leaf, each with whether the path goes right from it.
+    path: Vec<(Arc<RopeNodeType>, bool)>,
+    leaf: Arc<RopeNodeType>,
+    /// Offset of the leaf in the rope.
+    start: usize,
+}
+
+impl WordLeafCursor {
+    /// Descends by the node weights to the leaf containing `offset`, or to
+    /// the last leaf when `offset` is past the end.
+    fn seek(rope: &Rope, offset: usize) -> Self {
+        let mut cursor = WordLeafCursor {
+            path: vec![],
+            leaf: Arc::clone(&rope.root.0),
+            start: 0,
+        };
+        let mut offset = offset;
+
+        loop {
+            let current = Arc::clone(&cursor.leaf);
+            let RopeNodeType::Node(node) = current.as_ref() else {
+                return cursor;
+            };
+            let right = offset >= node.weight && node.right.is_not_none();
+
+            if right {
+                offset -= node.weight;
+                cursor.start += node.weight;
+            }
+
+            cursor.step_down(current, right);
+        }
+    }
+
+    fn value(&self) -> &str {
+        match self.leaf.as_ref() {
+            RopeNodeType::Leaf(leaf) => &leaf.value,
+            RopeNodeType::Node(_) | RopeNodeType::None => "",
+        }
+    }
+
+    /// Moves to the next leaf, false past the last one.
+    fn next(&mut self) -> bool {
+        let len = self.value().len();
+
+        while let Some((node, right)) = self.path.pop() {
+            if right || !matches!(node.as_ref(), RopeNodeType::Node(n) if n.right.is_not_none()) {
+                continue;
+            }
+
+            self.start += len;
+            self.step_down(node, true);
+            self.descend(false);
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Moves to the previous leaf, false before the first one.
+    fn prev(&mut self) -> bool {
+        while let Some((node, right)) = self.path.pop() {
+            if !right || !matches!(node.as_ref(), RopeNodeType::Node(n) if n.left.is_not_none()) {
+                continue;
+            }
+
+            self.step_down(node, false);
+            self.descend(true);
+            self.start -= self.value().len();
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Goes down to the leftmost or rightmost leaf below the current node.
+    fn descend(&mut self, rightmost: bool) {
+        loop {
+            let current = Arc::clone(&self.leaf);
+            let RopeNodeType::Node(node) = current.as_ref() else {
+                return;
+            };
+            let right = match rightmost {
+                true => node.right.is_not_none(),
+                false => !node.left.is_not_none(),
+            };
+
+            self.step_down(current, right);
+        }
+    }
+
+    fn step_down(&mut self, node: Arc<RopeNodeType>, right: bool) {
+        let RopeNodeType::Node(n) = node.as_ref() else {
+            return;
+        };
+        let child = match right {
+            true => &n.right,
+            false => &n.left,
+        };
+
+        self.leaf = Arc::clone(&child.0);
+        self.path.push((node, right));
+    }
+}
+
+/// Chars with their offsets, starting at an offset.
+struct CharsFrom {
+    leafs: WordLeafCursor,
+    pos: usize,
+}
+
+impl CharsFrom {
+    fn new(rope: &Rope, offset: usize) -> Self {
+        let leafs = WordLeafCursor::seek(rope, offset);
+        let value = leafs.value();
+        let mut pos = offset.saturating_sub(leafs.start).min(value.len());
+
+        while !value.is_char_boundary(pos) {
+            pos += 1;
+        }
+
+        CharsFrom { leafs, pos }
+    }
+}
+
+impl Iterator for CharsFrom {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.leafs.value()[self.pos..].chars().next() {
+                let at = self.leafs.start + self.pos;
+                self.pos += c.len_utf8();
+
+                return Some((at, c));
+            }
+
+            if !self.leafs.next() {
+                return None;
+            }
+
+            self.pos = 0;
+        }
+    }
+}
+
+/// Chars with their offsets in reverse, starting before an offset.
+struct CharsBefore {
+    leafs: WordLeafCursor,
+    end: usize,
+}
+
+impl CharsBefore {
+    fn new(rope: &Rope, offset: usize) -> Self {
+        let leafs = WordLeafCursor::seek(rope, offset);
+        let value = leafs.value();
+        let mut end = offset.saturating_sub(leafs.start).min(value.len());
+
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        CharsBefore { leafs, end }
+    }
+}
+
+impl Iterator for CharsBefore {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.leafs.value()[..self.end].chars().next_back() {
+                self.end -= c.len_utf8();
+
+                return Some((self.leafs.start + self.end, c));
+            }
+
+            if !self.leafs.prev() {
+                return None;
+            }
+
+            self.end = self.leafs.value().len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rope::rope_node::{Leaf, RopeNode};
+
+    /// Splits `value` into leafs of 3 chars to cross leaf boundaries.
+    fn rope(value: &str) -> Rope {
+        let chars: Vec<char> = value.chars().collect();
+
+        Rope::from_iter(chars.chunks(3).map(|chunk| {
+            RopeNode::from(Arc::new(RopeNodeType::Leaf(Leaf {
+                value: chunk.iter().collect(),
+            })))
+        }))
+    }
+
+    #[test]
+    fn chars_from_and_before_test() {
+        let text = "let föö_bar = a.b;  x";
+        let rope = rope(text);
+
+        for offset in 0..=text.len() + 1 {
+            let from = text.ceil_char_boundary(offset.min(text.len()));
+            let before = text.floor_char_boundary(offset.min(text.len()));
+
+            assert!(CharsFrom::new(&rope, offset)
+                .eq(text[from..].char_indices().map(|(i, c)| (from + i, c))));
+            assert!(CharsBefore::new(&rope, offset).eq(text[..before].char_indices().rev()));
+        }
+    }
+
+    #[test]
+    fn word_boundaries_test() {
+        let rope = rope("let föö_bar = a.b;  x");
+
+        assert_eq!(rope.next_word_boundary(0), 3);
+        assert_eq!(rope.next_word_boundary(3), 4);
+        assert_eq!(rope.next_word_boundary(4), 13);
+        assert_eq!(rope.next_word_boundary(16), 17);
+        assert_eq!(rope.next_word_boundary(22), 23);
+        assert_eq!(rope.next_word_boundary(23), 23);
+
+        assert_eq!(rope.prev_word_boundary(23), 22);
+        assert_eq!(rope.prev_word_boundary(22), 20);
+        assert_eq!(rope.prev_word_boundary(10), 4);
+        assert_eq!(rope.prev_word_boundary(3), 0);
+        assert_eq!(rope.prev_word_boundary(0), 0);
+    }
+
+    #[test]
+    fn word_at_test() {
+        let rope = rope("(defun kebab-case? (x) x)");
+
+        assert_eq!(rope.word_at(8), Some(7..12));
+        assert_eq!(rope.word_at(12), Some(7..12));
+        assert_eq!(rope.word_at(0), None);
+        assert_eq!(rope.word_at_with(8, &WordChars::lisp()), Some(7..18));
+        assert_eq!(rope.word_at_with(1, &WordChars::lisp()), Some(1..6));
+        assert_eq!(rope.word_at(25), None);
+    }
+
+    #[test]
+    fn sentence_boundaries_test() {
+        let rope = rope("Hi there. \"Is it 1.5?\"  Yes!Sure. End");
+
+        assert_eq!(rope.next_sentence_boundary(0), 10);
+        assert_eq!(rope.next_sentence_boundary(10), 24);
+        assert_eq!(rope.next_sentence_boundary(24), 34);
+        assert_eq!(rope.next_sentence_boundary(34), 37);
+
+        assert_eq!(rope.prev_sentence_boundary(37), 34);
+        assert_eq!(rope.prev_sentence_boundary(34), 24);
+        assert_eq!(rope.prev_sentence_boundary(20), 10);
+        assert_eq!(rope.prev_sentence_boundary(10), 0);
+    }
+
+    #[test]
+    fn paragraph_boundaries_test() {
+        let rope = rope("first\nstill first\n\n  \nsecond\n\nthird");
+
+        assert_eq!(rope.next_paragraph_boundary(0), 22);
+        assert_eq!(rope.next_paragraph_boundary(19), 22);
+        assert_eq!(rope.next_paragraph_boundary(22), 30);
+        assert_eq!(rope.next_paragraph_boundary(30), 35);
+
+        assert_eq!(rope.prev_paragraph_boundary(35), 30);
+        assert_eq!(rope.prev_paragraph_boundary(30), 22);
+        assert_eq!(rope.prev_paragraph_boundary(25), 22);
+        assert_eq!(rope.prev_paragraph_boundary(22), 0);
+    }
+}